@@ -0,0 +1,54 @@
+//! Report the breaking changes between two serde-version snapshots
+//!
+//! ```text
+//! serde-version-check <previous snapshot> <current snapshot>
+//! ```
+//!
+//! The snapshots are `VersionSnapshot` serialized in Json (`.json` extension) or in Toml.
+//! Exits with the code 1 when breaking changes are found, and with the code 2 on errors.
+
+use serde_version::{check_compatibility, VersionSnapshot};
+use std::path::Path;
+use std::process::exit;
+
+fn parse_snapshot<'a>(path: &Path, input: &'a str) -> Result<VersionSnapshot<'a>, String> {
+    let result = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(input).map_err(|err| err.to_string()),
+        _ => toml::from_str(input).map_err(|err| err.to_string()),
+    };
+    result.map_err(|err| format!("Invalid snapshot {}: {}", path.display(), err))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path.display(), err))
+}
+
+fn run(previous: &Path, current: &Path) -> Result<bool, String> {
+    let previous_input = read(previous)?;
+    let current_input = read(current)?;
+    let previous = parse_snapshot(previous, &previous_input)?;
+    let current = parse_snapshot(current, &current_input)?;
+
+    let changes = check_compatibility(&previous, &current);
+    for change in &changes {
+        println!("{}", change);
+    }
+    Ok(changes.is_empty())
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.len() != 2 {
+        eprintln!("Usage: serde-version-check <previous snapshot> <current snapshot>");
+        exit(2);
+    }
+
+    match run(Path::new(&args[0]), Path::new(&args[1])) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(2);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const PREVIOUS: &str = r#"latest = { "my_crate::A" = 1 }
+versions = { "my_crate::A" = [1] }
+
+[[groups]]
+uri = "core:1"
+types = { "my_crate::A" = 1 }
+"#;
+
+const COMPATIBLE: &str = r#"latest = { "my_crate::A" = 2 }
+versions = { "my_crate::A" = [1, 2] }
+
+[[groups]]
+uri = "core:1"
+types = { "my_crate::A" = 1 }
+
+[[groups]]
+uri = "core:2"
+types = { "my_crate::A" = 2 }
+"#;
+
+const BREAKING: &str = r#"latest = { "my_crate::A" = 2 }
+versions = { "my_crate::A" = [2] }
+
+[[groups]]
+uri = "core:1"
+types = { "my_crate::A" = 2 }
+"#;
+
+/// A directory with the snapshot files of a test
+struct Snapshots(PathBuf);
+
+impl Snapshots {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "serde_version_check_{}_{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn write(&self, file_name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(file_name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for Snapshots {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn check(previous: &Path, current: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_serde-version-check"))
+        .arg(previous)
+        .arg(current)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn compatible_snapshots_succeed() {
+    let snapshots = Snapshots::new("compatible");
+    let output = check(
+        &snapshots.write("previous.toml", PREVIOUS),
+        &snapshots.write("current.toml", COMPATIBLE),
+    );
+
+    assert_eq!(Some(0), output.status.code());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn breaking_snapshots_fail() {
+    let snapshots = Snapshots::new("breaking");
+    let output = check(
+        &snapshots.write("previous.toml", PREVIOUS),
+        &snapshots.write("current.toml", BREAKING),
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("The version uri core:1 was redefined with different versions"));
+    assert!(stdout.contains("The version 1 of the type my_crate::A was removed"));
+}
+
+#[test]
+fn json_snapshots_are_supported() {
+    let snapshots = Snapshots::new("json");
+    let previous: toml::Value = toml::from_str(PREVIOUS).unwrap();
+    let output = check(
+        &snapshots.write("previous.json", &serde_json::to_string(&previous).unwrap()),
+        &snapshots.write("current.toml", COMPATIBLE),
+    );
+
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn invalid_snapshot_fails_with_an_error() {
+    let snapshots = Snapshots::new("invalid");
+    let output = check(
+        &snapshots.write("previous.toml", "groups = 5"),
+        &snapshots.write("current.toml", COMPATIBLE),
+    );
+
+    assert_eq!(Some(2), output.status.code());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Invalid snapshot"));
+}
+
+#[test]
+fn missing_arguments_fail_with_usage() {
+    let output = Command::new(env!("CARGO_BIN_EXE_serde-version-check"))
+        .output()
+        .unwrap();
+
+    assert_eq!(Some(2), output.status.code());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Usage: serde-version-check"));
+}
@@ -0,0 +1,160 @@
+//! Testing utilities for the migrations of versioned types
+//!
+//! The types are deserialized from `serde_test` tokens with versioning support:
+//!
+//! ```rust
+//! #![feature(min_specialization)]
+//! # #[macro_use]
+//! # extern crate serde_version;
+//! # use serde::Deserialize;
+//! use serde_version_test::{assert_all_versions_de_tokens, assert_de_versioned_tokens, version_map_of, Token};
+//!
+//! #[derive(Deserialize)]
+//! #[serde(rename = "A")]
+//! struct Av1 {
+//!     a: u8,
+//! }
+//!
+//! #[derive(Deserialize, DeserializeVersioned, PartialEq, Debug)]
+//! #[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+//! struct A {
+//!     b: u8,
+//! }
+//!
+//! impl From<Av1> for A {
+//!     fn from(v: Av1) -> Self {
+//!         Self { b: v.a }
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let v1 = [
+//!     Token::Struct { name: "A", len: 1 },
+//!     Token::Str("a"),
+//!     Token::U8(5),
+//!     Token::StructEnd,
+//! ];
+//! let v2 = [
+//!     Token::Struct { name: "A", len: 1 },
+//!     Token::Str("b"),
+//!     Token::U8(5),
+//!     Token::StructEnd,
+//! ];
+//!
+//! assert_de_versioned_tokens(&A { b: 5 }, version_map_of::<A>(1), &v1);
+//! assert_all_versions_de_tokens(&A { b: 5 }, &[(1, &v1), (2, &v2)]);
+//! # }
+//! ```
+
+mod de;
+mod error;
+
+pub use de::Deserializer;
+pub use error::Error;
+pub use serde_test::Token;
+
+use serde_version::{DefaultVersionMap, DeserializeVersioned, VersionMap, Versioned};
+use std::fmt::Debug;
+
+/// A version map with the version of `T`
+pub fn version_map_of<T: ?Sized>(version: usize) -> DefaultVersionMap<'static> {
+    let mut version_map = DefaultVersionMap::new();
+    version_map.insert(std::any::type_name::<T>(), version);
+    version_map
+}
+
+/// Deserialize tokens with versioning support
+///
+/// The tokens are deserialized within a `serde_version::scope`, so the content
+/// buffered by serde is also deserialized with versioning support.
+///
+/// # Returns
+/// The deserialized value, or the error that occurred, all the tokens must be consumed
+pub fn deserialize_versioned_tokens<'de, T, VM>(
+    version_map: VM,
+    tokens: &'de [Token],
+) -> Result<T, serde_version::Error<Error>>
+where
+    T: DeserializeVersioned<'de, VM>,
+    VM: VersionMap,
+{
+    let mut de = Deserializer::new(tokens);
+    let value = serde_version::scope(version_map.clone(), || {
+        let de = serde_version::VersionedDeserializer::new(&mut de, version_map.clone());
+        T::deserialize_versioned(de, version_map)
+    })
+    .map_err(serde_version::Error::reduce)?;
+    match de.remaining() {
+        0 => Ok(value),
+        remaining => Err(serde_version::Error::Message(format!(
+            "{} remaining tokens",
+            remaining
+        ))),
+    }
+}
+
+/// Assert that the tokens are deserialized to `value` with `version_map`
+pub fn assert_de_versioned_tokens<'de, T, VM>(value: &T, version_map: VM, tokens: &'de [Token])
+where
+    T: DeserializeVersioned<'de, VM> + PartialEq + Debug,
+    VM: VersionMap,
+{
+    match deserialize_versioned_tokens::<T, VM>(version_map, tokens) {
+        Ok(de) => assert_eq!(*value, de),
+        Err(err) => panic!("tokens failed to deserialize: {}", err),
+    }
+}
+
+/// Assert that the tokens fail to deserialize with `version_map` with the message `error`
+pub fn assert_de_versioned_tokens_error<'de, T, VM>(
+    version_map: VM,
+    tokens: &'de [Token],
+    error: &str,
+) where
+    T: DeserializeVersioned<'de, VM> + Debug,
+    VM: VersionMap,
+{
+    match deserialize_versioned_tokens::<T, VM>(version_map, tokens) {
+        Ok(de) => panic!("tokens deserialized to {:?}, expected error: {}", de, error),
+        Err(err) => assert_eq!(error, err.to_string()),
+    }
+}
+
+/// Assert that the fixture of each version declared in `#[versions]` is migrated to `value`
+///
+/// Each fixture is the tokens of `value` in a version of `T`, they are deserialized
+/// with this version of `T`.
+/// All the versions of `T` must have a fixture, including the latest one.
+pub fn assert_all_versions_de_tokens<'de, T>(value: &T, fixtures: &[(usize, &'de [Token])])
+where
+    T: Versioned + DeserializeVersioned<'de, DefaultVersionMap<'static>> + PartialEq + Debug,
+{
+    let type_id = std::any::type_name::<T>();
+    let missing = T::VERSIONS
+        .iter()
+        .filter(|version| !fixtures.iter().any(|(v, _)| v == *version))
+        .collect::<Vec<_>>();
+    assert!(
+        missing.is_empty(),
+        "no fixture for the versions {:?} of {}",
+        missing,
+        type_id
+    );
+
+    for (version, tokens) in fixtures {
+        assert!(
+            T::VERSIONS.contains(version),
+            "version {} is not declared by {}, the versions are {:?}",
+            version,
+            type_id,
+            T::VERSIONS
+        );
+        match deserialize_versioned_tokens::<T, _>(version_map_of::<T>(*version), tokens) {
+            Ok(de) => assert_eq!(*value, de, "version {} of {}", version, type_id),
+            Err(err) => panic!(
+                "version {} of {} failed to deserialize: {}",
+                version, type_id, err
+            ),
+        }
+    }
+}
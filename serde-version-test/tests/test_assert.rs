@@ -0,0 +1,91 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version;
+
+use serde::Deserialize;
+use serde_version_test::{
+    assert_all_versions_de_tokens, assert_de_versioned_tokens, assert_de_versioned_tokens_error,
+    version_map_of, Token,
+};
+
+#[derive(Deserialize)]
+#[serde(rename = "A")]
+struct Av1 {
+    a: u8,
+}
+
+#[derive(Deserialize, DeserializeVersioned, PartialEq, Debug)]
+#[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+struct A {
+    b: u8,
+}
+
+impl From<Av1> for A {
+    fn from(v: Av1) -> Self {
+        Self { b: v.a }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Container {
+    a: A,
+}
+
+const V1: &[Token] = &[
+    Token::Struct { name: "A", len: 1 },
+    Token::Str("a"),
+    Token::U8(5),
+    Token::StructEnd,
+];
+const V2: &[Token] = &[
+    Token::Struct { name: "A", len: 1 },
+    Token::Str("b"),
+    Token::U8(5),
+    Token::StructEnd,
+];
+
+#[test]
+fn assert_de_versioned_tokens_works() {
+    assert_de_versioned_tokens(
+        &Container { a: A { b: 5 } },
+        version_map_of::<A>(1),
+        &[
+            Token::Struct {
+                name: "Container",
+                len: 1,
+            },
+            Token::Str("a"),
+            Token::Struct { name: "A", len: 1 },
+            Token::Str("a"),
+            Token::U8(5),
+            Token::StructEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn assert_de_versioned_tokens_error_works() {
+    assert_de_versioned_tokens_error::<A, _>(
+        version_map_of::<A>(3),
+        V1,
+        &format!("Unknown version 3 for type {}", std::any::type_name::<A>()),
+    );
+    assert_de_versioned_tokens_error::<A, _>(
+        version_map_of::<A>(2),
+        &[V2, &[Token::Unit]].concat(),
+        "1 remaining tokens",
+    );
+}
+
+#[test]
+fn assert_all_versions_de_tokens_works() {
+    assert_all_versions_de_tokens(&A { b: 5 }, &[(1, V1), (2, V2)]);
+}
+
+#[test]
+#[should_panic(expected = "no fixture for the versions [1]")]
+fn assert_all_versions_de_tokens_without_fixture_fails() {
+    assert_all_versions_de_tokens(&A { b: 5 }, &[(2, V2)]);
+}
@@ -0,0 +1,89 @@
+//! A command line tool upgrading Toml files in place to the latest version group
+//!
+//! ```shell
+//! cargo run --example migrate --features toml-support -- [--dry-run] <files>...
+//! ```
+//!
+//! The types of the documents are application specific,
+//! so each application builds its own tool with `serde_version::toml::migrate_file`.
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate lazy_static;
+
+use serde::{Deserialize, Serialize};
+use serde_version::VersionGroupURIs;
+use serde_version_derive::DeserializeVersioned;
+use std::convert::TryInto;
+
+#[derive(Deserialize)]
+#[serde(rename = "A")]
+struct Av1 {
+    a: u8,
+}
+
+#[derive(Serialize, Deserialize, DeserializeVersioned)]
+#[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+struct A {
+    b: u8,
+}
+
+impl From<Av1> for A {
+    fn from(v: Av1) -> Self {
+        Self { b: v.a }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    a: A,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("migrate.example", "1.0.0") => { A => 1, },
+        ("migrate.example", "2.0.0") => { A => 2, },
+    }
+}
+
+fn main() {
+    let mut dry_run = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            _ => paths.push(arg),
+        }
+    }
+
+    let latest: VersionGroupURIs = vec!["migrate.example:2.0.0".try_into().unwrap()].into();
+
+    let mut failed = false;
+    for path in paths {
+        match serde_version::toml::migrate_file::<Config, _, _>(
+            &path,
+            &*VERSIONS,
+            &(),
+            &latest,
+            dry_run,
+        ) {
+            Ok(migration) if migration.is_changed() => {
+                println!("{}:", path);
+                if dry_run {
+                    print!("{}", migration.diff());
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,333 @@
+//! Serialization utilities for binary formats
+//!
+//! Binary formats are not self-describing, so the version header can't be written as
+//! a `v = [...]` entry. A compact header is written before the payload instead:
+//! - the magic number `SVER`
+//! - the number of version uris, as a little endian `u16`
+//! - each version uri, as its length in a little endian `u16` followed by its UTF-8 bytes
+//!
+//! `write_header`, `read_header` and `read_header_from_reader` can be used with any binary format,
+//! `serialize` and `deserialize` encode the payload with bincode.
+
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, Error,
+    VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionMap, VersionedDeserializer,
+};
+use bincode::Options;
+use failure::Fail;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+/// Magic number starting the version header
+pub const MAGIC: &[u8; 4] = b"SVER";
+
+/// Error of the version header
+#[derive(Debug, Fail)]
+pub enum HeaderError {
+    #[fail(display = "Invalid magic number, the input has no version header")]
+    InvalidMagic,
+    #[fail(display = "The version header is truncated")]
+    Truncated,
+    #[fail(display = "Invalid version uri {}", uri)]
+    InvalidUri { uri: String },
+    #[fail(display = "Too many version uris: {}", count)]
+    TooManyUris { count: usize },
+    #[fail(display = "The version uri {} is too long", uri)]
+    UriTooLong { uri: String },
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+}
+impl_from_enum! {
+    HeaderError::Io => std::io::Error,
+}
+
+/// Deserialization error
+#[derive(Debug, Fail)]
+pub enum DeserializeError {
+    #[fail(display = "{}", _0)]
+    Header(HeaderError),
+    #[fail(display = "{}", _0)]
+    De(Error<Error<::bincode::Error>>),
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+}
+impl_from_enum! {
+    DeserializeError::Header => HeaderError,
+    DeserializeError::De => Error<Error<::bincode::Error>>,
+    DeserializeError::AggregateError => AggregateVersionMapError,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Header(HeaderError),
+    #[fail(display = "{}", _0)]
+    Bincode(::bincode::Error),
+}
+impl_from_enum! {
+    SerializeError::Header => HeaderError,
+    SerializeError::Bincode => ::bincode::Error,
+}
+
+/// The bincode options of `bincode::serialize`
+fn bincode_options() -> impl Options {
+    bincode::options()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
+
+/// Write the version header
+///
+/// # Parameters
+/// - `writer`: where to write the header
+/// - `uris`: The uris to write in the version header
+pub fn write_header<W: Write>(mut writer: W, uris: &VersionGroupURIs) -> Result<(), HeaderError> {
+    let count =
+        u16::try_from(uris.len()).map_err(|_| HeaderError::TooManyUris { count: uris.len() })?;
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&count.to_le_bytes())?;
+    for uri in uris.iter() {
+        let uri = uri.to_string();
+        let len =
+            u16::try_from(uri.len()).map_err(|_| HeaderError::UriTooLong { uri: uri.clone() })?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(uri.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Read the version header
+///
+/// # Parameters
+/// - `input`: the header followed by the payload
+///
+/// # Returns
+/// The version uris, borrowed from `input`, and the payload
+pub fn read_header(input: &[u8]) -> Result<(VersionGroupURIs<'_>, &[u8]), HeaderError> {
+    let input = input
+        .strip_prefix(&MAGIC[..])
+        .ok_or(HeaderError::InvalidMagic)?;
+    let (count, mut input) = read_u16(input)?;
+
+    let mut uris = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (len, rest) = read_u16(input)?;
+        if rest.len() < len as usize {
+            return Err(HeaderError::Truncated);
+        }
+        let (uri, rest) = rest.split_at(len as usize);
+        let uri = std::str::from_utf8(uri).map_err(|_| HeaderError::InvalidUri {
+            uri: String::from_utf8_lossy(uri).into_owned(),
+        })?;
+        uris.push(
+            VersionGroupURI::try_from(uri)
+                .map_err(|err| HeaderError::InvalidUri { uri: err.source })?,
+        );
+        input = rest;
+    }
+    Ok((uris.into(), input))
+}
+
+fn read_u16(input: &[u8]) -> Result<(u16, &[u8]), HeaderError> {
+    if input.len() < 2 {
+        return Err(HeaderError::Truncated);
+    }
+    let (bytes, rest) = input.split_at(2);
+    Ok((u16::from_le_bytes([bytes[0], bytes[1]]), rest))
+}
+
+/// Read the version header from a reader
+///
+/// Only the version header is read, the reader is left at the start of the payload.
+///
+/// # Parameters
+/// - `reader`: the header followed by the payload
+///
+/// # Returns
+/// The version uris
+pub fn read_header_from_reader<R: Read>(
+    mut reader: R,
+) -> Result<VersionGroupURIs<'static>, HeaderError> {
+    let mut magic = [0u8; 4];
+    read_exact(&mut reader, &mut magic)?;
+    if &magic != MAGIC {
+        return Err(HeaderError::InvalidMagic);
+    }
+    let count = read_u16_from_reader(&mut reader)?;
+
+    let mut uris = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let len = read_u16_from_reader(&mut reader)?;
+        let mut uri = vec![0u8; len as usize];
+        read_exact(&mut reader, &mut uri)?;
+        let uri = String::from_utf8(uri).map_err(|err| HeaderError::InvalidUri {
+            uri: String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        })?;
+        uris.push(
+            VersionGroupURI::try_from(uri)
+                .map_err(|err| HeaderError::InvalidUri { uri: err.source })?,
+        );
+    }
+    Ok(uris.into())
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), HeaderError> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        std::io::ErrorKind::UnexpectedEof => HeaderError::Truncated,
+        _ => HeaderError::Io(err),
+    })
+}
+
+fn read_u16_from_reader<R: Read>(reader: &mut R) -> Result<u16, HeaderError> {
+    let mut bytes = [0u8; 2];
+    read_exact(reader, &mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+/// Deserialize a bincode payload with versioning support
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: the version header followed by the bincode payload
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize<'de, T, VMR, AGG>(
+    input: &'de [u8],
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    deserialize_with_options(input, resolver, aggregate, bincode_options())
+}
+
+/// Deserialize a bincode payload with versioning support, with a size limit
+///
+/// The deserialization fails when the payload would need more than `limit` bytes,
+/// for instance when a corrupted length prefix announces a huge collection.
+///
+/// See `deserialize`.
+pub fn deserialize_with_limit<'de, T, VMR, AGG>(
+    input: &'de [u8],
+    resolver: &VMR,
+    aggregate: &AGG,
+    limit: u64,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    deserialize_with_options(
+        input,
+        resolver,
+        aggregate,
+        bincode_options().with_limit(limit),
+    )
+}
+
+fn deserialize_with_options<'de, T, VMR, AGG, O>(
+    input: &'de [u8],
+    resolver: &VMR,
+    aggregate: &AGG,
+    options: O,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+    O: Options,
+{
+    let (uris, payload) = read_header(input)?;
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let mut de = bincode::Deserializer::from_slice(payload, options);
+    let de = VersionedDeserializer::new(&mut de, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })?)
+}
+
+/// Deserialize a bincode payload with versioning support from a reader
+///
+/// The payload is streamed from the reader, it is never loaded in memory as a whole.
+/// Only the version header and the payload are read, so several payloads can be read
+/// from the same reader.
+///
+/// See `deserialize`.
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    mut reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let uris = read_header_from_reader(&mut reader)?;
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let mut de = bincode::Deserializer::with_reader(reader, bincode_options());
+    let de = VersionedDeserializer::new(&mut de, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })?)
+}
+
+/// Serialize a value with bincode and its version header
+///
+/// # Parameters
+/// - `writer`: where to write the version header and the payload
+/// - `value`: The value to serialize
+/// - `uris`: The uris to write in the version header
+pub fn serialize_into<W: Write, T: Serialize>(
+    mut writer: W,
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<(), SerializeError> {
+    write_header(&mut writer, uris)?;
+    bincode_options().serialize_into(writer, value)?;
+    Ok(())
+}
+
+/// Serialize a value with bincode and its version header
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to write in the version header
+///
+/// # Returns
+/// The version header followed by the payload, or the error that occurred
+pub fn serialize<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<Vec<u8>, SerializeError> {
+    let mut output = Vec::new();
+    serialize_into(&mut output, value, uris)?;
+    Ok(output)
+}
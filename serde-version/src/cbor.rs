@@ -0,0 +1,199 @@
+//! Serialization utilities for the CBOR format
+//!
+//! A document is a versioned envelope: a header map with the version header `v`,
+//! followed by the payload.
+//!
+//! The payload is deserialized through `VersionedDeserializer` with the version map
+//! of the header, it can't borrow from the input.
+//! `ciborium` only deserializes owned values and doesn't expose its deserializer,
+//! so the version map is provided to the payload with a `scope`.
+
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, UncoveredType,
+    VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionMap, VersionRegistry,
+};
+use failure::Fail;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+/// Deserialization error
+#[derive(Debug, Fail)]
+pub enum DeserializeError {
+    #[fail(display = "{}", _0)]
+    Cbor(::ciborium::de::Error<std::io::Error>),
+    #[fail(display = "Invalid version uri {}", uri)]
+    InvalidUri { uri: String },
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+}
+impl_from_enum! {
+    DeserializeError::Cbor => ::ciborium::de::Error<std::io::Error>,
+    DeserializeError::AggregateError => AggregateVersionMapError,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Cbor(::ciborium::ser::Error<std::io::Error>),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    SerializeError::Cbor => ::ciborium::ser::Error<std::io::Error>,
+    SerializeError::UncoveredType => UncoveredType,
+}
+
+/// The header map of an envelope
+#[derive(Deserialize)]
+struct Header {
+    v: Vec<String>,
+}
+
+/// The payload of an envelope
+///
+/// It is deserialized through `VersionedDeserializer` with the version map of the current scope.
+struct Payload<T>(T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Payload<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::scope::deserialize_versioned(deserializer).map(Payload)
+    }
+}
+
+fn deserialize_envelope<R, T, VMR, AGG>(
+    mut reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let header: Header = ::ciborium::de::from_reader(&mut reader)?;
+    let uris: VersionGroupURIs = header
+        .v
+        .into_iter()
+        .map(|uri| {
+            VersionGroupURI::try_from(uri)
+                .map_err(|err| DeserializeError::InvalidUri { uri: err.source })
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into();
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let Payload(value) = crate::scope(version_map, || {
+        ::ciborium::de::from_reader::<Payload<T>, _>(reader)
+    })?;
+    Ok(value)
+}
+
+/// Deserialize a CBOR envelope with versioning support from a reader
+///
+/// Only the envelope is read, so several envelopes can be read from the same reader.
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `reader`: the header map followed by the payload
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    deserialize_envelope(reader, resolver, aggregate)
+}
+
+/// Deserialize a CBOR envelope with versioning support
+///
+/// See `deserialize_from_reader`.
+pub fn deserialize<T, VMR, AGG>(
+    input: &[u8],
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    deserialize_envelope(input, resolver, aggregate)
+}
+
+/// Write a value in a CBOR envelope with its version header
+///
+/// # Parameters
+/// - `writer`: where to write the envelope
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+pub fn serialize_into<W: Write, T: Serialize>(
+    mut writer: W,
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<(), ::ciborium::ser::Error<std::io::Error>> {
+    ::ciborium::ser::into_writer(uris, &mut writer)?;
+    ::ciborium::ser::into_writer(value, writer)
+}
+
+/// Serialize a value in a CBOR envelope with its version header
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<Vec<u8>, ::ciborium::ser::Error<std::io::Error>> {
+    let mut output = Vec::new();
+    serialize_into(&mut output, value, uris)?;
+    Ok(output)
+}
+
+/// Serialize a value in a CBOR envelope with the latest version header
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `registry`: The current version groups
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize_latest<T: Serialize>(
+    value: &T,
+    registry: &VersionRegistry,
+) -> Result<Vec<u8>, SerializeError> {
+    let uris = registry.latest_uris::<T>()?;
+    Ok(serialize(value, &uris)?)
+}
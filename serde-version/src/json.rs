@@ -0,0 +1,540 @@
+//! Serialization utilities for the JSON format
+//!
+//! A document is a versioned envelope: a header object with the version header `v`,
+//! followed by the payload.
+//! ```json
+//! {"v":["org.my.company:1.0.2"]}
+//! {"name":"my config name"}
+//! ```
+//!
+//! The payload is streamed through `VersionedDeserializer`, so `deserialize_from_reader`
+//! never loads the whole document in memory.
+//!
+//! `migrate_file` upgrades a JSON envelope in place to the latest version of its types.
+//!
+//! `records` reads the records of a JSON Lines stream, like an event log, where each record
+//! is written with its own version header.
+
+use crate::migrate::{write_file_atomic, Migration};
+use crate::value::{deserialize_versioned_from_value, DeserializeValueError, VERSION_HEADER_KEY};
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DefaultVersionMap, DeserializeVersioned, Error,
+    UncoveredType, VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionMap,
+    VersionRegistry, VersionedDeserializer,
+};
+use failure::Fail;
+use serde::de::Deserializer;
+use serde::{Deserialize, Serialize};
+use serde_json::de::Read as JsonRead;
+use std::borrow::Borrow;
+use std::io::{BufRead, Lines, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Deserialization error
+#[derive(Debug, Fail)]
+pub enum DeserializeError {
+    #[fail(display = "{}", _0)]
+    Json(::serde_json::Error),
+    #[fail(display = "{}", _0)]
+    De(Error<Error<::serde_json::Error>>),
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+}
+impl_from_enum! {
+    DeserializeError::Json => ::serde_json::Error,
+    DeserializeError::De => Error<Error<::serde_json::Error>>,
+    DeserializeError::AggregateError => AggregateVersionMapError,
+}
+
+/// Migration error
+#[derive(Debug, Fail)]
+pub enum MigrateError {
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+    #[fail(display = "{}", _0)]
+    De(DeserializeError),
+    #[fail(display = "{}", _0)]
+    Ser(::serde_json::Error),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    MigrateError::Io => std::io::Error,
+    MigrateError::De => DeserializeError,
+    MigrateError::Ser => ::serde_json::Error,
+    MigrateError::UncoveredType => UncoveredType,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Json(::serde_json::Error),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    SerializeError::Json => ::serde_json::Error,
+    SerializeError::UncoveredType => UncoveredType,
+}
+
+fn deserialize_envelope<'de, T, R, VMR, AGG>(
+    de: &mut ::serde_json::Deserializer<R>,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: JsonRead<'de>,
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let uris: VersionGroupURIs = serde::Deserialize::deserialize(&mut *de)?;
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let de = VersionedDeserializer::new(de, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })?)
+}
+
+/// Deserialize a JSON envelope with versioning support
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: the header object followed by the payload
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize<'de, T, VMR, AGG>(
+    input: &'de str,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut de = ::serde_json::Deserializer::from_str(input);
+    let value = deserialize_envelope(&mut de, resolver, aggregate)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Deserialize a JSON envelope with versioning support from a reader
+///
+/// Only the envelope is read, so several envelopes can be read from the same reader.
+/// The reader is read byte per byte, wrap it in a `BufReader` when it is not buffered.
+///
+/// See `deserialize`.
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut de = ::serde_json::Deserializer::from_reader(reader);
+    deserialize_envelope(&mut de, resolver, aggregate)
+}
+
+/// Write a value in a JSON envelope with its version header
+///
+/// The header object and the payload are written on their own line.
+///
+/// # Parameters
+/// - `writer`: where to write the envelope
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+pub fn serialize_into<W: Write, T: Serialize>(
+    mut writer: W,
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<(), ::serde_json::Error> {
+    ::serde_json::to_writer(&mut writer, uris)?;
+    writer.write_all(b"\n").map_err(::serde_json::Error::io)?;
+    ::serde_json::to_writer(&mut writer, value)?;
+    writer.write_all(b"\n").map_err(::serde_json::Error::io)
+}
+
+/// Serialize a value in a JSON envelope with its version header
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<String, ::serde_json::Error> {
+    let mut output = Vec::new();
+    serialize_into(&mut output, value, uris)?;
+    // unwrap: serde_json writes valid UTF-8
+    Ok(String::from_utf8(output).unwrap())
+}
+
+/// Serialize a value in a JSON envelope with the latest version header
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `registry`: The current version groups
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize_latest<T: Serialize>(
+    value: &T,
+    registry: &VersionRegistry,
+) -> Result<String, SerializeError> {
+    let uris = registry.latest_uris::<T>()?;
+    Ok(serialize(value, &uris)?)
+}
+
+/// Upgrade a JSON envelope to the latest version of its types
+///
+/// The envelope is deserialized with versioning support and serialized
+/// with the version header `uris`.
+///
+/// # Generic Parameters
+/// - `T`: type of the payload
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: the header object followed by the payload
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `uris`: The uris to serialize in the version header, usually the latest ones
+///
+/// # Returns
+/// The migrated envelope or the error that occurred
+pub fn migrate_str<T, VMR, AGG>(
+    input: &str,
+    resolver: &VMR,
+    aggregate: &AGG,
+    uris: &VersionGroupURIs,
+) -> Result<String, MigrateError>
+where
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM> + Serialize,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let value: T = deserialize(input, resolver, aggregate)?;
+    Ok(serialize(&value, uris)?)
+}
+
+/// Upgrade a JSON envelope to the latest version header of the registry
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// See `migrate_str`.
+pub fn migrate_str_latest<T, AGG>(
+    input: &str,
+    registry: &VersionRegistry,
+    aggregate: &AGG,
+) -> Result<String, MigrateError>
+where
+    T: for<'de> DeserializeVersioned<'de, DefaultVersionMap<'static>> + Serialize,
+    AGG: AggregateVersionMap,
+{
+    let uris = registry.latest_uris::<T>()?;
+    migrate_str::<T, _, _>(input, &registry.resolver(), aggregate, &uris)
+}
+
+/// Upgrade a JSON envelope file in place to the latest version of its types
+///
+/// See `migrate_str`.
+///
+/// The file is replaced atomically, see `migrate::write_file_atomic`.
+///
+/// # Parameters
+/// - `path`: path of the JSON file
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `uris`: The uris to serialize in the version header, usually the latest ones
+/// - `dry_run`: when `true`, the file is not written. Use `Migration::diff` to report the changes.
+///
+/// # Returns
+/// The original and migrated documents or the error that occurred.
+/// The file is written only when the document changed.
+pub fn migrate_file<T, VMR, AGG>(
+    path: impl AsRef<Path>,
+    resolver: &VMR,
+    aggregate: &AGG,
+    uris: &VersionGroupURIs,
+    dry_run: bool,
+) -> Result<Migration, MigrateError>
+where
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM> + Serialize,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let path = path.as_ref();
+    let original = std::fs::read_to_string(path)?;
+    let migrated = migrate_str::<T, _, _>(&original, resolver, aggregate, uris)?;
+    let migration = Migration { original, migrated };
+
+    if !dry_run && migration.is_changed() {
+        write_file_atomic(path, migration.migrated.as_bytes())?;
+    }
+    Ok(migration)
+}
+
+/// Upgrade a JSON envelope file in place to the latest version header of the registry
+///
+/// See `migrate_str_latest` and `migrate_file`.
+pub fn migrate_file_latest<T, AGG>(
+    path: impl AsRef<Path>,
+    registry: &VersionRegistry,
+    aggregate: &AGG,
+    dry_run: bool,
+) -> Result<Migration, MigrateError>
+where
+    T: for<'de> DeserializeVersioned<'de, DefaultVersionMap<'static>> + Serialize,
+    AGG: AggregateVersionMap,
+{
+    let uris = registry.latest_uris::<T>()?;
+    migrate_file::<T, _, _>(path, &registry.resolver(), aggregate, &uris, dry_run)
+}
+
+/// Error while reading a record of a JSON Lines stream
+#[derive(Debug, Fail)]
+pub enum ReadRecordError {
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+    #[fail(display = "{}", _0)]
+    Json(::serde_json::Error),
+    #[fail(display = "{}", _0)]
+    Value(DeserializeValueError<::serde_json::Error>),
+}
+impl_from_enum! {
+    ReadRecordError::Io => std::io::Error,
+    ReadRecordError::Json => ::serde_json::Error,
+    ReadRecordError::Value => DeserializeValueError<::serde_json::Error>,
+}
+
+/// Error of a record of a JSON Lines stream
+#[derive(Debug, Fail)]
+#[fail(display = "Line {}: {}", line, error)]
+pub struct RecordError {
+    /// The line of the record that failed, starting at 1
+    pub line: usize,
+    /// The error of the record
+    pub error: ReadRecordError,
+}
+
+/// Iterator over the records of a JSON Lines stream, see `records`
+pub struct Records<'a, R, T, VMR, AGG>
+where
+    AGG: AggregateVersionMap,
+{
+    lines: Lines<R>,
+    line: usize,
+    /// The version map of the last header line, aggregated when the header line is read
+    version_map: Option<AGG::VM>,
+    resolver: &'a VMR,
+    aggregate: &'a AGG,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/// Read the records of a JSON Lines stream with versioning support
+///
+/// Each line is either:
+/// - a header line, an object with only the version header `v`:
+///   the following records are written with this version header
+/// - a record object with an inline version tag `v`
+/// - a record without version tag, written with the version header of the previous header line.
+///   It is streamed through `VersionedDeserializer`, without building a `serde_json::Value`,
+///   unless the line contains the string `"v"` or an escaped character.
+///
+/// An object with only the entry `v` is always a header line: a record with an inline version
+/// tag must have at least one other entry. Records without entries are written after a header line.
+///
+/// Each line is parsed once. The version map of a header line is aggregated when the header line
+/// is read: a header line that can't be aggregated yields its error, and the records without
+/// version tag that follow it fail with `DeserializeValueError::MissingVersionHeader`.
+///
+/// ```json
+/// {"v":["events:1"]}
+/// {"type":"created","id":1}
+/// {"v":["events:2"],"type":"renamed","id":1,"name":"a"}
+/// ```
+///
+/// Blank lines are skipped. A record that can't be read doesn't stop the iteration,
+/// its error is yielded with its line.
+///
+/// The version maps of the records with an inline version tag are aggregated for each record,
+/// use a `CachedAggregator` to reuse the version maps of the version headers already read.
+///
+/// # Generic Parameters
+/// - `T`: type of the records
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `reader`: the JSON Lines stream
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// An iterator over the records migrated to their latest version
+pub fn records<'a, R, T, VMR, AGG>(
+    reader: R,
+    resolver: &'a VMR,
+    aggregate: &'a AGG,
+) -> Records<'a, R, T, VMR, AGG>
+where
+    R: BufRead,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    Records {
+        lines: reader.lines(),
+        line: 0,
+        version_map: None,
+        resolver,
+        aggregate,
+        _phantom: PhantomData,
+    }
+}
+
+impl<'a, R, T, VMR, AGG> Records<'a, R, T, VMR, AGG>
+where
+    R: BufRead,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    /// Read a line, returns `None` for blank lines and header lines
+    fn read_record(&mut self, line: &str) -> Result<Option<T>, ReadRecordError> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        // A line without the string `"v"` has no version tag, unless the key is escaped
+        if !line.contains(r#""v""#) && !line.contains('\\') {
+            let mut de = ::serde_json::Deserializer::from_str(line);
+            let record = self.deserialize_record(&mut de)?;
+            de.end()?;
+            return Ok(Some(record));
+        }
+
+        let value: ::serde_json::Value = ::serde_json::from_str(line)?;
+        match value {
+            ::serde_json::Value::Object(mut object) if object.contains_key(VERSION_HEADER_KEY) => {
+                if object.len() == 1 {
+                    let header = object.remove(VERSION_HEADER_KEY).unwrap_or_default();
+                    self.read_header(&header)?;
+                    Ok(None)
+                } else {
+                    Ok(Some(deserialize_versioned_from_value(
+                        ::serde_json::Value::Object(object),
+                        self.resolver,
+                        self.aggregate,
+                    )?))
+                }
+            }
+            value => self.deserialize_record(value).map(Some),
+        }
+    }
+
+    /// Aggregate the version map of a header line
+    fn read_header(&mut self, header: &::serde_json::Value) -> Result<(), ReadRecordError> {
+        // The records following a header that can't be read are not deserialized
+        // with the previous one
+        self.version_map = None;
+        let uris: VersionGroupURIs = <Vec<VersionGroupURI> as Deserialize>::deserialize(header)
+            .map_err(DeserializeValueError::Header)?
+            .into();
+        let version_map = self
+            .aggregate
+            .aggregate_version_maps(&uris, self.resolver)
+            .map_err(DeserializeValueError::from)?;
+        self.version_map = Some(version_map);
+        Ok(())
+    }
+
+    /// Deserialize a record without version tag with the version map of the last header line
+    fn deserialize_record<'de, D>(&self, deserializer: D) -> Result<T, ReadRecordError>
+    where
+        D: Deserializer<'de, Error = ::serde_json::Error>,
+    {
+        let version_map: &AggregatedVersionMap = self
+            .version_map
+            .as_ref()
+            .ok_or(DeserializeValueError::MissingVersionHeader)?
+            .borrow();
+
+        crate::scope(version_map, || {
+            DeserializeVersioned::deserialize_versioned(
+                VersionedDeserializer::new(deserializer, version_map),
+                version_map,
+            )
+        })
+        .map_err(|err| match err.reduce() {
+            Error::DeserializeError(err) if err.is_syntax() || err.is_eof() => {
+                ReadRecordError::Json(err)
+            }
+            err => DeserializeValueError::De(err).into(),
+        })
+    }
+}
+
+impl<'a, R, T, VMR, AGG> Iterator for Records<'a, R, T, VMR, AGG>
+where
+    R: BufRead,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    type Item = Result<T, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            self.line += 1;
+            let record = line
+                .map_err(ReadRecordError::from)
+                .and_then(|line| self.read_record(&line));
+            match record {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => continue,
+                Err(error) => {
+                    return Some(Err(RecordError {
+                        line: self.line,
+                        error,
+                    }))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Kubernetes-style dispatch on `apiVersion` and `kind`
+//!
+//! Each document of a heterogeneous stream carries its own `apiVersion` and `kind` entries:
+//! ```yaml
+//! apiVersion: apps/v1
+//! kind: Deployment
+//! replicas: 3
+//! ```
+//!
+//! The `apiVersion` `group/version` is the version uri `group:version`,
+//! an `apiVersion` without group, like `v1`, is in the `core` api group.
+//! The `kind` selects the Rust type registered in a `KindRegistry`, and the document is
+//! deserialized in the latest version of this type with the version group of the `apiVersion`.
+
+use crate::value::VersionedValue;
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, Error,
+    VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionMap, VersionedDeserializer,
+};
+use failure::Fail;
+use serde::Deserialize;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Key of the version uri of a document
+pub const API_VERSION_KEY: &str = "apiVersion";
+/// Key of the kind of a document
+pub const KIND_KEY: &str = "kind";
+/// Api group of the `apiVersion` without group
+pub const CORE_API_GROUP: &str = "core";
+
+/// Convert an `apiVersion` to a version uri
+///
+/// ```rust
+/// # use serde_version::kind::api_version_to_uri;
+/// #
+/// assert_eq!("apps:v1", api_version_to_uri("apps/v1").unwrap().to_string());
+/// assert_eq!("core:v1", api_version_to_uri("v1").unwrap().to_string());
+/// ```
+pub fn api_version_to_uri(api_version: &str) -> Option<VersionGroupURI<'static>> {
+    let uri = match api_version.find('/') {
+        Some(index) => format!("{}:{}", &api_version[..index], &api_version[index + 1..]),
+        None => format!("{}:{}", CORE_API_GROUP, api_version),
+    };
+    VersionGroupURI::try_from(uri).ok()
+}
+
+/// Error while loading a document with `KindRegistry`
+#[derive(Debug, Fail)]
+pub enum KindError<E: Fail> {
+    /// The document has no `apiVersion` or no `kind` entry
+    #[fail(display = "Missing entry '{}'", key)]
+    MissingEntry { key: &'static str },
+    /// The `apiVersion` or the `kind` entry is not a string
+    #[fail(display = "{}", _0)]
+    InvalidEntry(E),
+    /// The `apiVersion` is not `group/version` or `version`
+    #[fail(
+        display = "Invalid apiVersion {}, expected \"group/version\" or \"version\"",
+        api_version
+    )]
+    InvalidApiVersion { api_version: String },
+    /// No type is registered for this kind
+    #[fail(display = "Unknown kind {} in api group {}", kind, api_group)]
+    UnknownKind { api_group: String, kind: String },
+    /// The version group of the `apiVersion` can't be found
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+    /// The document can't be deserialized
+    #[fail(display = "{}", _0)]
+    De(Error<E>),
+}
+impl_from_enum! {
+    KindError<E: Fail>::AggregateError => AggregateVersionMapError,
+}
+
+type LoadKind<V, O> =
+    fn(V, &AggregatedVersionMap) -> Result<O, Error<<V as serde::Deserializer<'static>>::Error>>;
+
+fn load_kind<V, O, T>(value: V, version_map: &AggregatedVersionMap) -> Result<O, Error<V::Error>>
+where
+    V: VersionedValue<'static>,
+    T: Deserialize<'static> + Into<O>,
+{
+    let de = VersionedDeserializer::new(value, version_map);
+    crate::scope(version_map, || {
+        <T as DeserializeVersioned<'static, &AggregatedVersionMap>>::deserialize_versioned(
+            de,
+            version_map,
+        )
+    })
+    .map(Into::into)
+    .map_err(Error::reduce)
+}
+
+/// The Rust types of the kinds of each api group
+///
+/// # Generic Parameters
+/// - `V`: type of the value tree of a document
+/// - `O`: type of the loaded documents, each registered type converts into it
+///
+/// ```rust
+/// # #![feature(min_specialization)]
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # #[macro_use]
+/// # extern crate serde_version_derive;
+/// # use serde::Deserialize;
+/// # use serde_version::kind::KindRegistry;
+/// #
+/// #[derive(Deserialize, DeserializeVersioned)]
+/// #[versions(v(index = 1, self))]
+/// struct Deployment {
+///     replicas: u32,
+/// }
+///
+/// enum Object {
+///     Deployment(Deployment),
+/// }
+///
+/// impl From<Deployment> for Object {
+///     fn from(v: Deployment) -> Self {
+///         Object::Deployment(v)
+///     }
+/// }
+///
+/// version_group_resolver_static! {
+///     pub VERSIONS = {
+///         ("apps", "v1") => { Deployment => 1, },
+///     }
+/// }
+///
+/// # fn main() {
+/// # #[cfg(feature = "json-support")]
+/// # {
+/// let mut kinds = KindRegistry::<serde_json::Value, Object>::new();
+/// kinds.register::<Deployment>("apps", "Deployment");
+///
+/// let document = serde_json::json!({ "apiVersion": "apps/v1", "kind": "Deployment", "replicas": 3 });
+/// match kinds.load(document, &*VERSIONS, &()).unwrap() {
+///     Object::Deployment(deployment) => assert_eq!(3, deployment.replicas),
+/// }
+/// # }
+/// # }
+/// ```
+pub struct KindRegistry<V: VersionedValue<'static>, O> {
+    kinds: HashMap<(String, String), LoadKind<V, O>>,
+}
+
+impl<V: VersionedValue<'static>, O> Default for KindRegistry<V, O> {
+    fn default() -> Self {
+        Self {
+            kinds: HashMap::new(),
+        }
+    }
+}
+
+impl<V: VersionedValue<'static>, O> KindRegistry<V, O> {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the latest version of the type of a kind
+    ///
+    /// A type already registered for this kind is replaced.
+    pub fn register<T>(&mut self, api_group: &str, kind: &str) -> &mut Self
+    where
+        T: Deserialize<'static> + Into<O>,
+    {
+        self.kinds.insert(
+            (api_group.to_owned(), kind.to_owned()),
+            load_kind::<V, O, T>,
+        );
+        self
+    }
+
+    /// Whether a type is registered for a kind
+    pub fn contains(&self, api_group: &str, kind: &str) -> bool {
+        self.kinds
+            .contains_key(&(api_group.to_owned(), kind.to_owned()))
+    }
+
+    /// Load a document with versioning support
+    ///
+    /// The `apiVersion` and `kind` entries are removed from the document,
+    /// the document is deserialized with the version group of the `apiVersion`
+    /// in the type registered for its kind.
+    ///
+    /// # Parameters
+    /// - `value`: the value tree of the document
+    /// - `resolver`: resolver to find the version groups to use
+    /// - `aggregate`: aggregator to build the version map of the version group
+    ///
+    /// # Returns
+    /// The loaded document or the error that occurred
+    pub fn load<VMR, AGG>(
+        &self,
+        mut value: V,
+        resolver: &VMR,
+        aggregate: &AGG,
+    ) -> Result<O, KindError<V::Error>>
+    where
+        V::Error: Fail,
+        VMR::VM: VersionMap,
+        VMR: VersionGroupResolver,
+        AGG: AggregateVersionMap,
+    {
+        let api_version = take_string(&mut value, API_VERSION_KEY)?;
+        let kind = take_string(&mut value, KIND_KEY)?;
+        let uri =
+            api_version_to_uri(&api_version).ok_or(KindError::InvalidApiVersion { api_version })?;
+
+        let key = (uri.api_group().to_owned(), kind);
+        let load = self.kinds.get(&key).ok_or_else(|| KindError::UnknownKind {
+            api_group: key.0.clone(),
+            kind: key.1.clone(),
+        })?;
+
+        let uris: VersionGroupURIs = vec![uri].into();
+        let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+        load(value, version_map.borrow()).map_err(KindError::De)
+    }
+
+    /// Load the documents of a stream with versioning support
+    ///
+    /// See `load`.
+    ///
+    /// # Returns
+    /// The loaded documents or the first error that occurred
+    pub fn load_all<VMR, AGG, I>(
+        &self,
+        values: I,
+        resolver: &VMR,
+        aggregate: &AGG,
+    ) -> Result<Vec<O>, KindError<V::Error>>
+    where
+        V::Error: Fail,
+        VMR::VM: VersionMap,
+        VMR: VersionGroupResolver,
+        AGG: AggregateVersionMap,
+        I: IntoIterator<Item = V>,
+    {
+        values
+            .into_iter()
+            .map(|value| self.load(value, resolver, aggregate))
+            .collect()
+    }
+}
+
+fn take_string<V: VersionedValue<'static>>(
+    value: &mut V,
+    key: &'static str,
+) -> Result<String, KindError<V::Error>>
+where
+    V::Error: Fail,
+{
+    let entry = value
+        .take_entry(key)
+        .ok_or(KindError::MissingEntry { key })?;
+    String::deserialize(entry).map_err(KindError::InvalidEntry)
+}
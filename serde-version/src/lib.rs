@@ -56,34 +56,71 @@ pub use lazy_static::*;
 #[macro_use]
 extern crate failure;
 
+// Reexport inventory for `register_version_group!`
+#[cfg(feature = "inventory-support")]
+#[doc(hidden)]
+pub use inventory;
+
 #[cfg(test)]
 extern crate quickcheck;
 #[cfg(test)]
 #[macro_use(quickcheck)]
 extern crate quickcheck_macros;
 
+#[macro_use]
+mod utils;
+
 mod deserializer;
+mod scope;
 mod seed;
 mod version_groups;
 mod version_map;
 mod visitor;
 
-#[macro_use]
-mod utils;
-
+#[cfg(feature = "bincode-support")]
+pub mod binary;
+#[cfg(feature = "cbor-support")]
+pub mod cbor;
+#[cfg(feature = "json-support")]
+pub mod json;
+pub mod kind;
+pub mod merge;
+pub mod migrate;
+#[cfg(feature = "msgpack-support")]
+pub mod msgpack;
+pub mod observer;
+pub mod option;
+pub mod scoped;
+#[cfg(feature = "tokio-support")]
+pub mod tokio;
 #[cfg(feature = "toml-support")]
 pub mod toml;
+pub mod value;
+#[cfg(feature = "yaml-support")]
+pub mod yaml;
 
 pub use deserializer::VersionedDeserializer;
+pub use scope::scope;
+pub use seed::VersionedSeed;
 use serde::de::{EnumAccess, MapAccess, SeqAccess};
 use std::fmt::Display;
 pub use version_groups::{
-    DefaultVersionGroupResolver, VersionGroupResolver, VersionGroupURI, VersionGroupURIs,
+    check_compatibility, latest_uris, latest_version_map, BreakingChange, ChainResolver,
+    DefaultVersionGroupResolver, DynVersionGroupResolver, KnownTypes, ManifestError, MapResolver,
+    OrResolver, RegisterVersionGroupError, RegistryResolver, TryFromError, TypeInMultipleApiGroups,
+    TypeVersionMismatch, UncoveredType, UnknownTypeId, VersionGroupAlreadyRegistered,
+    VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionManifest, VersionManifestGroup,
+    VersionRegistry, VersionSnapshot,
 };
+#[cfg(feature = "inventory-support")]
+pub use version_groups::{StaticVersionGroup, StaticVersionedType};
 pub use version_map::{
-    AggregateVersionMap, AggregateVersionMapError, DefaultVersionMap, TypeInMultipleVersionGroups,
-    UnknownVersionURI, VersionMap,
+    AggregateVersionMap, AggregateVersionMapError, AggregatedVersionMap, CachedAggregator,
+    ConflictPolicy, DefaultVersionMap, DeprecatedVersion, SunsetPolicy,
+    TypeInMultipleVersionGroups, UnknownVersionURI, UnsupportedVersion, UnsupportedVersionGroup,
+    VersionMap,
 };
+pub use visitor::{VersionedVisitor, VisitNewtypeAsSeq};
 
 /// Error used when a provided version number is not handled by current code
 #[derive(Debug, Hash, PartialEq, Eq, Fail)]
@@ -161,6 +198,120 @@ where
     }
 }
 
+/// Wrap a `SeqAccess` so that its elements are deserialized with versioning support
+///
+/// Use this in a hand-written `Deserialize` implementation that drives a `SeqAccess`
+/// manually and needs the version map to be propagated to its elements.
+///
+/// The returned access reports `Error<S::Error>`, use `Error::into_error` to get
+/// back the error of the wrapped access.
+///
+/// ```rust
+/// # use serde::de::{SeqAccess, Visitor};
+/// # use serde_version::{wrap_seq_access, VersionMap};
+/// #
+/// struct PairVisitor<VM>(VM);
+///
+/// impl<'de, VM: VersionMap> Visitor<'de> for PairVisitor<VM> {
+///     type Value = (u8, u8);
+///
+///     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         formatter.write_str("a pair")
+///     }
+///
+///     fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+///         let mut seq = wrap_seq_access(seq, self.0);
+///         let first = seq.next_element().map_err(|err| err.into_error())?;
+///         let second = seq.next_element().map_err(|err| err.into_error())?;
+///         match (first, second) {
+///             (Some(first), Some(second)) => Ok((first, second)),
+///             _ => Err(serde::de::Error::custom("expected two elements")),
+///         }
+///     }
+/// }
+/// ```
+pub fn wrap_seq_access<'de, S, VM>(seq_access: S, version_map: VM) -> VersionedVisitor<'de, S, VM>
+where
+    S: SeqAccess<'de>,
+    VM: VersionMap,
+{
+    VersionedVisitor::new(seq_access, version_map)
+}
+
+/// Wrap a `MapAccess` so that its keys and values are deserialized with versioning support
+///
+/// Use this in a hand-written `Deserialize` implementation that drives a `MapAccess`
+/// manually and needs the version map to be propagated to its entries.
+///
+/// The returned access reports `Error<M::Error>`, use `Error::into_error` to get
+/// back the error of the wrapped access.
+pub fn wrap_map_access<'de, M, VM>(map_access: M, version_map: VM) -> VersionedVisitor<'de, M, VM>
+where
+    M: MapAccess<'de>,
+    VM: VersionMap,
+{
+    VersionedVisitor::new(map_access, version_map)
+}
+
+/// The versions of a type
+///
+/// Use the `derive` feature to generate the implementation from `#[derive(DeserializeVersioned)]`
+/// and `#[versions(...)]` attribute.
+pub trait Versioned {
+    /// The version index of `self`, the latest version of the type
+    const LATEST_VERSION: usize;
+
+    /// All the version indexes supported, in ascending order
+    const VERSIONS: &'static [usize];
+}
+
+/// Collect the versioned types reachable from a type
+///
+/// It is implemented for all types, `#[derive(DeserializeVersioned)]` specializes it
+/// to add the type and visit the types of its fields.
+/// Use `#[derive(VersionedTypes)]` on the types that are not versioned
+/// to visit the types of their fields.
+/// The standard containers, references and tuples visit the types they contain.
+pub trait VersionedTypes {
+    /// Add the versioned types reachable from `Self` to `types`
+    fn versioned_types(types: &mut VersionedTypeSet);
+}
+
+impl<T: ?Sized> VersionedTypes for T {
+    default fn versioned_types(_types: &mut VersionedTypeSet) {}
+}
+
+/// The versioned types found by `VersionedTypes`
+#[derive(Debug, Clone, Default)]
+pub struct VersionedTypeSet {
+    visited: std::collections::HashSet<&'static str>,
+    latest: DefaultVersionMap<'static>,
+}
+
+impl VersionedTypeSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a type as visited
+    ///
+    /// Returns whether the type was not visited before, so cycles are visited only once.
+    pub fn visit(&mut self, type_id: &'static str) -> bool {
+        self.visited.insert(type_id)
+    }
+
+    /// Add a versioned type with its latest version
+    pub fn insert(&mut self, type_id: &'static str, latest_version: usize) {
+        self.latest.insert(type_id, latest_version);
+    }
+
+    /// The latest version of the versioned types found
+    pub fn into_version_map(self) -> DefaultVersionMap<'static> {
+        self.latest
+    }
+}
+
 /// Trait for versioning support during deserialization
 ///
 /// Use the `derive` feature to generate the implementation from `#[derive(DeserializeVersioned)]`
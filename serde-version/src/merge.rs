@@ -0,0 +1,96 @@
+//! Merging of configuration layers
+//!
+//! A configuration made of several layers (default, system, user, project...)
+//! must be migrated layer by layer, as each layer can be written with a different version header.
+//! Each layer is deserialized with versioning support in a partially populated representation,
+//! then the layers are merged with `Merge`.
+//!
+//! See `toml::merge_layers`.
+
+/// A partially populated representation that can be overridden by another layer
+///
+/// ```rust
+/// # use serde_version::merge::Merge;
+/// #
+/// #[derive(Default)]
+/// struct PartialConfig {
+///     name: Option<String>,
+///     threads: Option<usize>,
+/// }
+///
+/// impl Merge for PartialConfig {
+///     fn merge(&mut self, layer: Self) {
+///         self.name.merge(layer.name);
+///         self.threads.merge(layer.threads);
+///     }
+/// }
+///
+/// let mut config = PartialConfig { name: Some("default".to_owned()), threads: Some(4) };
+/// config.merge(PartialConfig { name: Some("project".to_owned()), threads: None });
+/// assert_eq!(Some("project"), config.name.as_deref());
+/// assert_eq!(Some(4), config.threads);
+/// ```
+pub trait Merge {
+    /// Override `self` with the values defined by `layer`
+    fn merge(&mut self, layer: Self);
+}
+
+impl<T> Merge for Option<T> {
+    fn merge(&mut self, layer: Self) {
+        if layer.is_some() {
+            *self = layer;
+        }
+    }
+}
+
+/// Merge layers in order, the last layers override the first ones
+pub fn merge_all<T, I>(layers: I) -> T
+where
+    T: Merge + Default,
+    I: IntoIterator<Item = T>,
+{
+    layers.into_iter().fold(T::default(), |mut merged, layer| {
+        merged.merge(layer);
+        merged
+    })
+}
+
+/// Tables are merged recursively, other values are overridden
+#[cfg(feature = "toml-support")]
+impl Merge for ::toml::Value {
+    fn merge(&mut self, layer: Self) {
+        match (self, layer) {
+            (::toml::Value::Table(table), ::toml::Value::Table(layer)) => {
+                for (key, value) in layer {
+                    match table.get_mut(&key) {
+                        Some(current) => current.merge(value),
+                        None => {
+                            table.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (current, layer) => *current = layer,
+        }
+    }
+}
+
+/// Objects are merged recursively, other values are overridden
+#[cfg(feature = "json-support")]
+impl Merge for ::serde_json::Value {
+    fn merge(&mut self, layer: Self) {
+        match (self, layer) {
+            (::serde_json::Value::Object(object), ::serde_json::Value::Object(layer)) => {
+                for (key, value) in layer {
+                    match object.get_mut(&key) {
+                        Some(current) => current.merge(value),
+                        None => {
+                            object.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (current, layer) => *current = layer,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Utilities to upgrade documents to the latest version group
+//!
+//! See `toml::migrate_file` and `json::migrate_file`.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A document before and after its migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// The document before the migration
+    pub original: String,
+    /// The document with the latest version header and types
+    pub migrated: String,
+}
+
+impl Migration {
+    /// Whether the migration changed the document
+    pub fn is_changed(&self) -> bool {
+        self.original != self.migrated
+    }
+
+    /// A line diff between the original and the migrated document
+    ///
+    /// Removed lines start with `-`, added lines with `+` and unchanged lines with a space.
+    /// The diff uses a memory linear in the number of lines.
+    ///
+    /// ```rust
+    /// # use serde_version::migrate::Migration;
+    /// #
+    /// let migration = Migration {
+    ///     original: "v = 1\na = 5\n".to_owned(),
+    ///     migrated: "v = 2\na = 5\n".to_owned(),
+    /// };
+    /// assert_eq!("-v = 1\n+v = 2\n a = 5\n", migration.diff());
+    /// ```
+    pub fn diff(&self) -> String {
+        let original = self.original.lines().collect::<Vec<_>>();
+        let migrated = self.migrated.lines().collect::<Vec<_>>();
+
+        let mut diff = String::new();
+        diff_lines(&original, &migrated, &mut diff);
+        diff
+    }
+}
+
+/// Write the diff of two lists of lines with Hirschberg's algorithm
+///
+/// The common prefix and suffix are trimmed first, a migration usually changes a few lines.
+fn diff_lines(original: &[&str], migrated: &[&str], diff: &mut String) {
+    let prefix = original
+        .iter()
+        .zip(migrated)
+        .take_while(|(original, migrated)| original == migrated)
+        .count();
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(migrated[prefix..].iter().rev())
+        .take_while(|(original, migrated)| original == migrated)
+        .count();
+    let (common_prefix, original_rest) = original.split_at(prefix);
+    let (original, common_suffix) = original_rest.split_at(original_rest.len() - suffix);
+    let migrated = &migrated[prefix..migrated.len() - suffix];
+
+    push_lines(diff, ' ', common_prefix);
+    match original.len() {
+        0 => push_lines(diff, '+', migrated),
+        _ if migrated.is_empty() => push_lines(diff, '-', original),
+        1 => match migrated.iter().position(|line| *line == original[0]) {
+            Some(index) => {
+                push_lines(diff, '+', &migrated[..index]);
+                push_lines(diff, ' ', original);
+                push_lines(diff, '+', &migrated[(index + 1)..]);
+            }
+            None => {
+                push_lines(diff, '-', original);
+                push_lines(diff, '+', migrated);
+            }
+        },
+        _ => {
+            // Split the migrated lines where the longest common subsequence
+            // crosses the middle of the original lines
+            let (original_start, original_end) = original.split_at(original.len() / 2);
+            let forward = lcs_lengths(original_start.iter(), migrated.iter());
+            let backward = lcs_lengths(original_end.iter().rev(), migrated.iter().rev());
+            let split = (0..=migrated.len())
+                .max_by_key(|&index| {
+                    (
+                        forward[index] + backward[migrated.len() - index],
+                        std::cmp::Reverse(index),
+                    )
+                })
+                .unwrap_or_default();
+            diff_lines(original_start, &migrated[..split], diff);
+            diff_lines(original_end, &migrated[split..], diff);
+        }
+    }
+    push_lines(diff, ' ', common_suffix);
+}
+
+/// The lengths of the longest common subsequences of `original` and each prefix of `migrated`
+fn lcs_lengths<'l, 's: 'l, I, J>(original: I, migrated: J) -> Vec<usize>
+where
+    I: Iterator<Item = &'l &'s str>,
+    J: Iterator<Item = &'l &'s str> + Clone,
+{
+    let mut previous = vec![0; migrated.clone().count() + 1];
+    let mut current = previous.clone();
+    for original in original {
+        for (index, migrated) in migrated.clone().enumerate() {
+            current[index + 1] = if original == migrated {
+                previous[index] + 1
+            } else {
+                current[index].max(previous[index + 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous
+}
+
+fn push_lines(diff: &mut String, prefix: char, lines: &[&str]) {
+    for line in lines {
+        diff.push(prefix);
+        diff.push_str(line);
+        diff.push('\n');
+    }
+}
+
+/// Replace the content of a file atomically
+///
+/// The content is written in a temporary file next to `path`, synced to the disk,
+/// then renamed to `path`: a crash never leaves a truncated file behind.
+/// The permissions of the existing file are kept.
+pub fn write_file_atomic(path: impl AsRef<Path>, contents: &[u8]) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let temp_path = temp_path(path);
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)?;
+    let result = (|| {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)?;
+        // Persist the rename
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            if let Ok(dir) = File::open(parent) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(file_name)
+}
@@ -0,0 +1,171 @@
+//! Serialization utilities for the MessagePack format
+//!
+//! A document is a versioned envelope: a header map with the version header `v`,
+//! followed by the payload. Structs are written as maps, so previous versions
+//! of a type can be read even if their fields differ.
+
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, Error, UncoveredType,
+    VersionGroupResolver, VersionGroupURIs, VersionMap, VersionRegistry, VersionedDeserializer,
+};
+use failure::Fail;
+use rmp_serde::decode::ReadSlice;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::io::{Read, Write};
+
+/// Deserialization error
+#[derive(Debug, Fail)]
+pub enum DeserializeError {
+    #[fail(display = "{}", _0)]
+    Msgpack(::rmp_serde::decode::Error),
+    #[fail(display = "{}", _0)]
+    De(Error<Error<::rmp_serde::decode::Error>>),
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+}
+impl_from_enum! {
+    DeserializeError::Msgpack => ::rmp_serde::decode::Error,
+    DeserializeError::De => Error<Error<::rmp_serde::decode::Error>>,
+    DeserializeError::AggregateError => AggregateVersionMapError,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Msgpack(::rmp_serde::encode::Error),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    SerializeError::Msgpack => ::rmp_serde::encode::Error,
+    SerializeError::UncoveredType => UncoveredType,
+}
+
+fn deserialize_envelope<'de, T, R, VMR, AGG>(
+    de: &mut ::rmp_serde::Deserializer<R>,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: ReadSlice<'de>,
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let uris: VersionGroupURIs = serde::Deserialize::deserialize(&mut *de)?;
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let de = VersionedDeserializer::new(de, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })?)
+}
+
+/// Deserialize a MessagePack envelope with versioning support
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: the header map followed by the payload
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize<'de, T, VMR, AGG>(
+    input: &'de [u8],
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut de = ::rmp_serde::Deserializer::from_read_ref(input);
+    deserialize_envelope(&mut de, resolver, aggregate)
+}
+
+/// Deserialize a MessagePack envelope with versioning support from a reader
+///
+/// Only the envelope is read, so several envelopes can be read from the same reader.
+///
+/// See `deserialize`.
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut de = ::rmp_serde::Deserializer::new(reader);
+    deserialize_envelope(&mut de, resolver, aggregate)
+}
+
+/// Write a value in a MessagePack envelope with its version header
+///
+/// # Parameters
+/// - `writer`: where to write the envelope
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+pub fn serialize_into<W: Write, T: Serialize>(
+    writer: W,
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<(), ::rmp_serde::encode::Error> {
+    let mut ser = ::rmp_serde::Serializer::new(writer).with_struct_map();
+    Serialize::serialize(uris, &mut ser)?;
+    Serialize::serialize(value, &mut ser)?;
+    Ok(())
+}
+
+/// Serialize a value in a MessagePack envelope with its version header
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<Vec<u8>, ::rmp_serde::encode::Error> {
+    let mut output = Vec::new();
+    serialize_into(&mut output, value, uris)?;
+    Ok(output)
+}
+
+/// Serialize a value in a MessagePack envelope with the latest version header
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `registry`: The current version groups
+///
+/// # Returns
+/// The envelope or the error that occurred
+pub fn serialize_latest<T: Serialize>(
+    value: &T,
+    registry: &VersionRegistry,
+) -> Result<Vec<u8>, SerializeError> {
+    let uris = registry.latest_uris::<T>()?;
+    Ok(serialize(value, &uris)?)
+}
@@ -0,0 +1,176 @@
+//! Observe the migrations of the versioned types
+//!
+//! `#[derive(DeserializeVersioned)]` notifies the installed `MigrationObserver`
+//! each time a value is deserialized from a previous version of its type,
+//! so the documents still using old versions can be measured.
+//!
+//! ```rust
+//! # use serde_version::observer::{set_migration_observer, MigrationObserver};
+//! # use std::sync::atomic::{AtomicUsize, Ordering};
+//! #
+//! #[derive(Default)]
+//! struct CountMigrations(AtomicUsize);
+//!
+//! impl MigrationObserver for CountMigrations {
+//!     fn on_migrate(&self, _type_id: &str, _from: usize, _to: usize) {
+//!         self.0.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! set_migration_observer(CountMigrations::default());
+//! ```
+//!
+//! There is a single observer for the whole process, installing an observer replaces
+//! the previous one. To notify several observers, install an observer forwarding the
+//! migrations to each of them. The tests of a crate run in parallel threads of the same
+//! process, so the tests installing an observer must not run concurrently:
+//! check all the migrations in a single test, or serialize the tests with a lock.
+//!
+//! Without observer, `notify` only reads an atomic flag, so the migrations don't
+//! contend on the lock of the observer.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Notified when a value is deserialized from a previous version of its type
+pub trait MigrationObserver: Send + Sync {
+    /// A value of `type_id` was deserialized from the version `from` and migrated to the version `to`
+    fn on_migrate(&self, type_id: &str, from: usize, to: usize);
+}
+
+impl<F> MigrationObserver for F
+where
+    F: Fn(&str, usize, usize) + Send + Sync,
+{
+    fn on_migrate(&self, type_id: &str, from: usize, to: usize) {
+        self(type_id, from, to)
+    }
+}
+
+lazy_static! {
+    static ref OBSERVER: RwLock<Option<Arc<dyn MigrationObserver>>> = RwLock::new(None);
+}
+
+/// Whether an observer is installed, checked before locking `OBSERVER`
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Install the observer notified of the migrations, it replaces the previous one
+pub fn set_migration_observer<O: MigrationObserver + 'static>(observer: O) {
+    let mut installed = OBSERVER.write().unwrap_or_else(PoisonError::into_inner);
+    *installed = Some(Arc::new(observer));
+    INSTALLED.store(true, Ordering::Release);
+}
+
+/// Remove the observer notified of the migrations
+pub fn clear_migration_observer() {
+    let mut installed = OBSERVER.write().unwrap_or_else(PoisonError::into_inner);
+    *installed = None;
+    INSTALLED.store(false, Ordering::Release);
+}
+
+/// Notify the installed observer of a migration
+///
+/// It is called by `#[derive(DeserializeVersioned)]`, call it from hand-written
+/// `DeserializeVersioned` implementations when a previous version is deserialized.
+pub fn notify(type_id: &str, from: usize, to: usize) {
+    if !INSTALLED.load(Ordering::Acquire) {
+        return;
+    }
+
+    let observer = OBSERVER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(observer) = observer {
+        observer.on_migrate(type_id, from, to);
+    }
+}
+
+/// Log the migrations with the `log` crate
+#[cfg(feature = "log-support")]
+#[derive(Debug, Clone, Copy)]
+pub struct LogObserver {
+    level: ::log::Level,
+}
+
+#[cfg(feature = "log-support")]
+impl LogObserver {
+    /// Log the migrations at `level`
+    pub fn new(level: ::log::Level) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "log-support")]
+impl Default for LogObserver {
+    /// Log the migrations at the `Debug` level
+    fn default() -> Self {
+        Self::new(::log::Level::Debug)
+    }
+}
+
+#[cfg(feature = "log-support")]
+impl MigrationObserver for LogObserver {
+    fn on_migrate(&self, type_id: &str, from: usize, to: usize) {
+        ::log::log!(
+            target: "serde_version::migration",
+            self.level,
+            "Migrated {} from version {} to version {}",
+            type_id,
+            from,
+            to
+        );
+    }
+}
+
+/// Emit the migrations as `tracing` events, with the fields `type_id`, `from` and `to`
+#[cfg(feature = "tracing-support")]
+#[derive(Debug, Clone, Copy)]
+pub struct TracingObserver {
+    level: ::tracing::Level,
+}
+
+#[cfg(feature = "tracing-support")]
+impl TracingObserver {
+    /// Emit the migrations at `level`
+    pub fn new(level: ::tracing::Level) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "tracing-support")]
+impl Default for TracingObserver {
+    /// Emit the migrations at the `DEBUG` level
+    fn default() -> Self {
+        Self::new(::tracing::Level::DEBUG)
+    }
+}
+
+#[cfg(feature = "tracing-support")]
+impl MigrationObserver for TracingObserver {
+    fn on_migrate(&self, type_id: &str, from: usize, to: usize) {
+        // The level of `tracing::event!` must be a constant
+        macro_rules! event {
+            ($level:expr) => {
+                ::tracing::event!(
+                    target: "serde_version::migration",
+                    $level,
+                    type_id,
+                    from,
+                    to,
+                    "Migrated {} from version {} to version {}",
+                    type_id,
+                    from,
+                    to
+                )
+            };
+        }
+        match self.level {
+            ::tracing::Level::ERROR => event!(::tracing::Level::ERROR),
+            ::tracing::Level::WARN => event!(::tracing::Level::WARN),
+            ::tracing::Level::INFO => event!(::tracing::Level::INFO),
+            ::tracing::Level::DEBUG => event!(::tracing::Level::DEBUG),
+            ::tracing::Level::TRACE => event!(::tracing::Level::TRACE),
+        }
+    }
+}
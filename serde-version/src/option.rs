@@ -0,0 +1,82 @@
+//! Versioning support for the content of `Option` fields
+//!
+//! Serde deserializes the content of an `Option<T>` with `T::deserialize`,
+//! so the `VersionedDeserializer` is bypassed and `T` is not migrated.
+//!
+//! Annotate the fields with `default, with = "serde_version::option"` and deserialize
+//! within a `serde_version::scope` to use the version map for their content
+//! (`serde_version::toml::deserialize` already does it).
+//! `default` keeps missing fields deserialized as `None`.
+//!
+//! ```rust
+//! # #![feature(min_specialization)]
+//! # use serde::{Deserialize, Serialize};
+//! #
+//! #[derive(Deserialize, Serialize)]
+//! struct HttpConfig {
+//!     port: u16,
+//! }
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct PartialConfig {
+//!     #[serde(default, with = "serde_version::option")]
+//!     http: Option<HttpConfig>,
+//! }
+//! ```
+
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Deserialize the content of an option with the version map of the current `scope`
+///
+/// Without a `scope`, the content is deserialized without versioning support.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct OptionVisitor<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for OptionVisitor<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("option")
+        }
+
+        #[inline]
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        #[inline]
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        #[inline]
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            crate::scope::deserialize(deserializer).map(Some)
+        }
+    }
+
+    deserializer.deserialize_option(OptionVisitor(std::marker::PhantomData))
+}
+
+/// Serialize an option, the serialization does not depend on versions
+pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    value.serialize(serializer)
+}
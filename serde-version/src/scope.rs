@@ -0,0 +1,172 @@
+use crate::version_map::VersionMapIter;
+use crate::{DeserializeVersioned, Error, VersionMap, VersionedDeserializer};
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+
+/// Object safe view of the version map of a scope
+trait ScopedVersionMap: Sync {
+    fn get(&self, type_id: &str) -> Option<usize>;
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, usize)> + '_>;
+}
+
+impl<VM: VersionMap> ScopedVersionMap for VM {
+    fn get(&self, type_id: &str) -> Option<usize> {
+        VersionMap::get(self, type_id)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, usize)> + '_> {
+        Box::new(VersionMapIter::iter(self))
+    }
+}
+
+thread_local! {
+    // The version maps are borrowed by the active `scope` calls of the thread,
+    // a pointer is only dereferenced while its `scope` call is on the stack.
+    static VERSION_MAPS: RefCell<Vec<*const (dyn ScopedVersionMap + 'static)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The version map of the current scope
+///
+/// It is only created by `with_scope_version_map` for a lifetime local to the call,
+/// so a `DeserializeVersioned` implementation can't keep it after its `scope` returns.
+#[derive(Clone, Copy)]
+struct ScopeVersionMap<'s>(&'s (dyn ScopedVersionMap + 's));
+
+impl<'s> VersionMap for ScopeVersionMap<'s> {
+    fn get(&self, type_id: &str) -> Option<usize> {
+        self.0.get(type_id)
+    }
+}
+
+impl<'s, 'i> VersionMapIter<'i> for ScopeVersionMap<'s> {
+    type Iter = Box<dyn Iterator<Item = (&'i str, usize)> + 'i>;
+
+    fn iter(&'i self) -> Self::Iter {
+        self.0.iter()
+    }
+}
+
+/// Call `f` with the version map of the innermost `scope`, if any
+///
+/// `f` must accept any lifetime, so the version map can't escape the call.
+fn with_scope_version_map<R, F>(f: F) -> R
+where
+    F: for<'s> FnOnce(Option<ScopeVersionMap<'s>>) -> R,
+{
+    let version_map = VERSION_MAPS.with(|version_maps| version_maps.borrow().last().copied());
+    // Safety: the innermost scope is on the stack during this call, see `scope`,
+    // and the reference doesn't outlive the call.
+    f(version_map.map(|version_map| ScopeVersionMap(unsafe { &*version_map })))
+}
+
+/// Pop the version map of a scope, even when unwinding
+struct ScopeGuard;
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        VERSION_MAPS.with(|version_maps| {
+            version_maps.borrow_mut().pop();
+        });
+    }
+}
+
+/// Call `f` with `version_map` used to deserialize the content buffered by serde
+///
+/// Serde buffers the content of `#[serde(flatten)]` fields and of internally tagged,
+/// adjacently tagged and untagged enums before deserializing it, so the
+/// `VersionedDeserializer` is bypassed.
+/// The helpers in `scoped` and `option` use the version map of the current scope instead.
+///
+/// Scopes can be nested, the innermost version map is used.
+///
+/// The version map is borrowed by the scope: the `DeserializeVersioned` implementations
+/// receive it for a lifetime they can't name, so they can't keep it after the scope returns.
+///
+/// ```rust,compile_fail
+/// # #![feature(min_specialization)]
+/// use serde::{Deserialize, Deserializer};
+/// use serde_version::{DeserializeVersioned, Error, VersionMap};
+/// use std::any::Any;
+/// use std::cell::RefCell;
+///
+/// thread_local! {
+///     static KEPT: RefCell<Vec<Box<dyn Any>>> = RefCell::new(Vec::new());
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Keep;
+///
+/// impl<'de, VM: VersionMap> DeserializeVersioned<'de, VM> for Keep {
+///     fn deserialize_versioned<D>(deserializer: D, version_map: VM) -> Result<Self, Error<D::Error>>
+///     where
+///         D: Deserializer<'de>,
+///     {
+///         // error: the version map may not live long enough
+///         KEPT.with(|kept| kept.borrow_mut().push(Box::new(version_map)));
+///         Keep::deserialize(deserializer).map_err(Error::DeserializeError)
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub fn scope<VM, F, R>(version_map: VM, f: F) -> R
+where
+    VM: VersionMap,
+    F: FnOnce() -> R,
+{
+    let version_map: &dyn ScopedVersionMap = &version_map;
+    // Safety: the pointer is popped by the guard before `version_map` is dropped,
+    // the lifetime is only erased to store it in the thread local.
+    let version_map = unsafe {
+        std::mem::transmute::<
+            *const (dyn ScopedVersionMap + '_),
+            *const (dyn ScopedVersionMap + 'static),
+        >(version_map)
+    };
+    VERSION_MAPS.with(|version_maps| version_maps.borrow_mut().push(version_map));
+    let _guard = ScopeGuard;
+    f()
+}
+
+/// Deserialize a value with the version map of the current `scope`
+///
+/// Without a `scope`, the value is deserialized without versioning support.
+pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    with_scope_version_map(|version_map| match version_map {
+        Some(version_map) => {
+            <T as DeserializeVersioned<'de, ScopeVersionMap>>::deserialize_versioned(
+                deserializer,
+                version_map,
+            )
+            .map_err(Error::into_error)
+        }
+        None => T::deserialize(deserializer),
+    })
+}
+
+/// Deserialize a value through `VersionedDeserializer` with the version map of the current `scope`
+///
+/// Used by the formats that only deserialize `DeserializeOwned` values, so the version map
+/// of the envelope can't be passed to the deserializer.
+/// Without a `scope`, the value is deserialized without versioning support.
+#[cfg_attr(not(feature = "cbor-support"), allow(dead_code))]
+pub(crate) fn deserialize_versioned<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    with_scope_version_map(|version_map| match version_map {
+        Some(version_map) => {
+            let deserializer = VersionedDeserializer::new(deserializer, version_map);
+            <T as DeserializeVersioned<'de, ScopeVersionMap>>::deserialize_versioned(
+                deserializer,
+                version_map,
+            )
+            .map_err(|err| err.reduce().into_error())
+        }
+        None => T::deserialize(deserializer),
+    })
+}
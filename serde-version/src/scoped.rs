@@ -0,0 +1,61 @@
+//! Versioning support for the content that serde deserializes without the `VersionedDeserializer`
+//!
+//! Serde buffers `#[serde(flatten)]` fields and the content of internally tagged, adjacently
+//! tagged and untagged enums before deserializing them, and `#[derive(Deserialize)]` deserializes
+//! the content of newtype structs with `Deserialize::deserialize`. The versioning of these
+//! types is bypassed.
+//!
+//! Annotate these fields with `with = "serde_version::scoped"` and deserialize within
+//! a `serde_version::scope` to use its version map for them
+//! (`serde_version::toml::deserialize` already does it).
+//!
+//! ```rust
+//! # #![feature(min_specialization)]
+//! # use serde::{Deserialize, Serialize};
+//! #
+//! #[derive(Deserialize, Serialize)]
+//! struct HttpConfig {
+//!     port: u16,
+//! }
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Config {
+//!     #[serde(flatten, with = "serde_version::scoped")]
+//!     http: HttpConfig,
+//!     name: String,
+//! }
+//!
+//! #[derive(Deserialize, Serialize)]
+//! #[serde(tag = "type")]
+//! enum Plugin {
+//!     Http(#[serde(with = "serde_version::scoped")] HttpConfig),
+//! }
+//!
+//! #[derive(Deserialize, Serialize)]
+//! #[serde(transparent)]
+//! struct MainHttpConfig(#[serde(with = "serde_version::scoped")] HttpConfig);
+//! ```
+//!
+//! Note: use `serde_version::option` for `Option` fields.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Deserialize a field with the version map of the current `scope`
+///
+/// Without a `scope`, the field is deserialized without versioning support.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    crate::scope::deserialize(deserializer)
+}
+
+/// Serialize a field, the serialization does not depend on versions
+pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    value.serialize(serializer)
+}
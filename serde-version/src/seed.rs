@@ -12,6 +12,7 @@ pub struct VersionedSeed<'v, S, VM> {
 }
 
 impl<'v, S, VM> VersionedSeed<'v, S, VM> {
+    /// Wrap `seed` and deserialize it with `version_map`
     pub fn new(seed: S, version_map: VM) -> Self {
         Self {
             seed,
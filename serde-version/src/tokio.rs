@@ -0,0 +1,224 @@
+//! Asynchronous deserialization of versioned frames from tokio readers
+//!
+//! A frame is the length of its content, as a big endian `u32`, followed by its content:
+//! the compact version header and the bincode payload of the `binary` module.
+//! The length prefix is the default one of the `LengthDelimitedCodec` of `tokio-util`.
+//!
+//! The frame is read asynchronously, then deserialized with versioning support,
+//! either on the current task or on the blocking pool for large frames.
+//! The bincode payload is limited to the maximum length of the frame.
+//!
+//! The functions reading frames are not cancel safe: when their future is dropped
+//! before completion, for instance by a `tokio::select!` branch completing first, the bytes
+//! already read are lost and the reader is left in the middle of a frame.
+//! Read the frames on a dedicated task and send the values through a channel
+//! to use them in `select!`.
+
+use crate::version_map::AggregateVersionMap;
+use crate::{binary, DeserializeVersioned, VersionGroupResolver, VersionGroupURIs, VersionMap};
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use failure::Fail;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Default maximum length of a frame: 8 MiB
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// Error while reading a frame
+#[derive(Debug, Fail)]
+pub enum FrameError {
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+    #[fail(
+        display = "The frame length {} exceeds the maximum {}",
+        length, max_length
+    )]
+    FrameTooLong { length: usize, max_length: usize },
+    #[fail(display = "{}", _0)]
+    De(binary::DeserializeError),
+    #[fail(display = "{}", _0)]
+    Join(::tokio::task::JoinError),
+}
+impl_from_enum! {
+    FrameError::Io => std::io::Error,
+    FrameError::De => binary::DeserializeError,
+    FrameError::Join => ::tokio::task::JoinError,
+}
+
+/// Error while writing a frame
+#[derive(Debug, Fail)]
+pub enum WriteFrameError {
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+    #[fail(display = "{}", _0)]
+    Ser(binary::SerializeError),
+    #[fail(display = "The frame length {} can't be written as a u32", length)]
+    FrameTooLong { length: usize },
+}
+impl_from_enum! {
+    WriteFrameError::Io => std::io::Error,
+    WriteFrameError::Ser => binary::SerializeError,
+}
+
+/// Read the content of a frame
+///
+/// Not cancel safe, see the module documentation.
+///
+/// # Parameters
+/// - `reader`: the reader of the frames
+/// - `max_length`: frames longer than this are rejected before their content is read
+///
+/// # Returns
+/// The content of the frame or the error that occurred
+pub async fn read_frame<R>(reader: &mut R, max_length: usize) -> Result<Vec<u8>, FrameError>
+where
+    R: AsyncRead + Unpin,
+{
+    let length = reader.read_u32().await? as usize;
+    if length > max_length {
+        return Err(FrameError::FrameTooLong { length, max_length });
+    }
+
+    let mut frame = vec![0u8; length];
+    reader.read_exact(&mut frame).await?;
+    Ok(frame)
+}
+
+/// Write a value in a frame with bincode and its version header
+///
+/// # Parameters
+/// - `writer`: the writer of the frames
+/// - `value`: The value to serialize
+/// - `uris`: The uris to write in the version header
+pub async fn write_frame<W, T>(
+    writer: &mut W,
+    value: &T,
+    uris: &VersionGroupURIs<'_>,
+) -> Result<(), WriteFrameError>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let frame = binary::serialize(value, uris)?;
+    let length = u32::try_from(frame.len()).map_err(|_| WriteFrameError::FrameTooLong {
+        length: frame.len(),
+    })?;
+
+    writer.write_u32(length).await?;
+    writer.write_all(&frame).await?;
+    Ok(())
+}
+
+/// Read a frame and deserialize it with versioning support
+///
+/// The frame is deserialized on the current task, use `deserialize_versioned_blocking`
+/// for large frames. Frames longer than `DEFAULT_MAX_FRAME_LENGTH` are rejected,
+/// use `deserialize_versioned_async_with_limit` to change it.
+///
+/// Not cancel safe, see the module documentation.
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `reader`: the reader of the frames
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub async fn deserialize_versioned_async<R, T, VMR, AGG>(
+    reader: &mut R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, FrameError>
+where
+    R: AsyncRead + Unpin,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    deserialize_versioned_async_with_limit(reader, resolver, aggregate, DEFAULT_MAX_FRAME_LENGTH)
+        .await
+}
+
+/// Read a frame and deserialize it with versioning support, with a maximum frame length
+///
+/// See `deserialize_versioned_async`.
+///
+/// # Parameters
+/// - `reader`: the reader of the frames
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `max_length`: frames longer than this are rejected before their content is read
+pub async fn deserialize_versioned_async_with_limit<R, T, VMR, AGG>(
+    reader: &mut R,
+    resolver: &VMR,
+    aggregate: &AGG,
+    max_length: usize,
+) -> Result<T, FrameError>
+where
+    R: AsyncRead + Unpin,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let frame = read_frame(reader, max_length).await?;
+    Ok(binary::deserialize_with_limit(
+        &frame,
+        resolver,
+        aggregate,
+        max_length as u64,
+    )?)
+}
+
+/// Read a frame and deserialize it with versioning support on the blocking pool
+///
+/// Frames shorter than `blocking_threshold` are deserialized on the current task.
+///
+/// Not cancel safe, see the module documentation.
+///
+/// See `deserialize_versioned_async`.
+///
+/// # Parameters
+/// - `reader`: the reader of the frames
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `max_length`: frames longer than this are rejected before their content is read
+/// - `blocking_threshold`: minimal length of the frames deserialized on the blocking pool
+pub async fn deserialize_versioned_blocking<R, T, VMR, AGG>(
+    reader: &mut R,
+    resolver: Arc<VMR>,
+    aggregate: Arc<AGG>,
+    max_length: usize,
+    blocking_threshold: usize,
+) -> Result<T, FrameError>
+where
+    R: AsyncRead + Unpin,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM> + Send + 'static,
+    VMR: VersionGroupResolver + Send + Sync + 'static,
+    AGG: AggregateVersionMap + Send + Sync + 'static,
+{
+    let frame = read_frame(reader, max_length).await?;
+    let limit = max_length as u64;
+    if frame.len() < blocking_threshold {
+        return Ok(binary::deserialize_with_limit(
+            &frame,
+            &*resolver,
+            &*aggregate,
+            limit,
+        )?);
+    }
+
+    let value = ::tokio::task::spawn_blocking(move || {
+        binary::deserialize_with_limit(&frame, &*resolver, &*aggregate, limit)
+    })
+    .await??;
+    Ok(value)
+}
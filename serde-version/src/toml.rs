@@ -1,12 +1,18 @@
 //! Serialization utilities for the Toml format
 
+use crate::merge::{merge_all, Merge};
+use crate::migrate::{write_file_atomic, Migration};
 use crate::version_map::AggregateVersionMap;
 use crate::{
-    AggregateVersionMapError, DeserializeVersioned, Error, VersionGroupResolver, VersionGroupURIs,
-    VersionMap, VersionedDeserializer,
+    AggregateVersionMapError, AggregatedVersionMap, DefaultVersionMap, DeserializeVersioned, Error,
+    KnownTypes, ManifestError, UncoveredType, VersionGroupResolver, VersionGroupURIs,
+    VersionManifest, VersionMap, VersionRegistry, VersionedDeserializer,
 };
 use failure::Fail;
 use serde::Serialize;
+use std::borrow::Borrow;
+use std::io::Read;
+use std::path::Path;
 
 /// Deserialization error
 #[derive(Debug, Fail)]
@@ -17,11 +23,69 @@ pub enum DeserializeError {
     De(Error<Error<::toml::de::Error>>),
     #[fail(display = "{}", 0)]
     AggregateError(AggregateVersionMapError),
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
 }
 impl_from_enum! {
     DeserializeError::Toml => ::toml::de::Error,
     DeserializeError::De => Error<Error<::toml::de::Error>>,
     DeserializeError::AggregateError => AggregateVersionMapError,
+    DeserializeError::Io => std::io::Error,
+}
+
+/// Manifest loading error
+#[derive(Debug, Fail)]
+pub enum LoadManifestError {
+    #[fail(display = "{}", _0)]
+    Toml(::toml::de::Error),
+    #[fail(display = "{}", _0)]
+    Manifest(ManifestError),
+}
+impl_from_enum! {
+    LoadManifestError::Toml => ::toml::de::Error,
+    LoadManifestError::Manifest => ManifestError,
+}
+
+/// Migration error
+#[derive(Debug, Fail)]
+pub enum MigrateError {
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+    #[fail(display = "{}", _0)]
+    De(DeserializeError),
+    #[fail(display = "{}", _0)]
+    Ser(::toml::ser::Error),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    MigrateError::Io => std::io::Error,
+    MigrateError::De => DeserializeError,
+    MigrateError::Ser => ::toml::ser::Error,
+    MigrateError::UncoveredType => UncoveredType,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Toml(::toml::ser::Error),
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    SerializeError::Toml => ::toml::ser::Error,
+    SerializeError::UncoveredType => UncoveredType,
+}
+
+/// Error of a configuration layer
+#[derive(Debug, Fail)]
+#[fail(display = "Layer {}: {}", index, error)]
+pub struct LayerError {
+    /// The index of the layer that failed
+    pub index: usize,
+    /// The deserialization error of the layer
+    pub error: DeserializeError,
 }
 
 /// Deserialize a Toml string with versioning support
@@ -53,6 +117,7 @@ where
     let uris: VersionGroupURIs = serde::Deserialize::deserialize(&mut de)?;
 
     let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
 
     // unwrap: the VersionGroupURIs ends with an array, so the token ']'
     // must exists as we successfully deserialized it.
@@ -60,11 +125,92 @@ where
 
     let input_left = input.split_at(end_of_version_header + 1).1;
     let mut de2 = ::toml::de::Deserializer::new(input_left);
-    let de3 = VersionedDeserializer::new(&mut de2, &version_map);
-    Ok(DeserializeVersioned::deserialize_versioned(
-        de3,
-        &version_map,
-    )?)
+    let de3 = VersionedDeserializer::new(&mut de2, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de3, version_map)
+    })?)
+}
+
+/// Deserialize a Toml document with versioning support from a reader
+///
+/// The Toml parser requires the whole document, so the reader is read to the end
+/// before the document is deserialized.
+///
+/// See `deserialize`.
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    mut reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    deserialize(&input, resolver, aggregate)
+}
+
+/// Deserialize Toml configuration layers with versioning support
+///
+/// Each layer is deserialized with the version groups of its own version header,
+/// so layers written with different versions are all migrated to the latest version.
+///
+/// # Generic Parameters
+/// - `P`: partially populated representation of a layer
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `layers`: Toml formatted layers, first entry of each layer is its version header
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized layers or the error of the first layer that failed
+pub fn deserialize_layers<'de, P, VMR, AGG, I>(
+    layers: I,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<Vec<P>, LayerError>
+where
+    VMR::VM: VersionMap,
+    P: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+    I: IntoIterator<Item = &'de str>,
+{
+    layers
+        .into_iter()
+        .enumerate()
+        .map(|(index, layer)| {
+            deserialize(layer, resolver, aggregate).map_err(|error| LayerError { index, error })
+        })
+        .collect()
+}
+
+/// Deserialize Toml configuration layers with versioning support and merge them
+///
+/// The last layers override the first ones, see `deserialize_layers` and `Merge`.
+///
+/// # Returns
+/// The merged layers or the error of the first layer that failed
+pub fn merge_layers<'de, P, VMR, AGG, I>(
+    layers: I,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<P, LayerError>
+where
+    VMR::VM: VersionMap,
+    P: DeserializeVersioned<'de, VMR::VM> + Merge + Default,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+    I: IntoIterator<Item = &'de str>,
+{
+    Ok(merge_all(deserialize_layers(layers, resolver, aggregate)?))
 }
 
 /// Serialize in a string a value formatted in Toml with its version header.
@@ -114,3 +260,153 @@ pub fn serialize<'s, T: Serialize>(
     serialize_inplace(&mut str, value, uris)?;
     Ok(str)
 }
+
+/// Serialize a value formatted in Toml with the latest version header.
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// # Generic Parameters
+/// - `T`: type to serialize
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `registry`: The current version groups
+///
+/// # Returns
+/// The formatted string or the error that occurred, if any.
+pub fn serialize_latest<T: Serialize>(
+    value: &T,
+    registry: &VersionRegistry,
+) -> Result<String, SerializeError> {
+    let uris = registry.latest_uris::<T>()?;
+    Ok(serialize(value, &uris)?)
+}
+
+/// Load the version groups of a Toml manifest in a new registry
+///
+/// See `VersionManifest` for the format of the manifest.
+///
+/// # Parameters
+/// - `input`: Toml formatted manifest
+/// - `known_types`: the type ids known to the binary, all type ids of the manifest must be known
+///
+/// # Returns
+/// The registry with the version groups of the manifest or the error that occurred
+pub fn load_manifest(
+    input: &str,
+    known_types: &KnownTypes,
+) -> Result<VersionRegistry, LoadManifestError> {
+    let manifest: VersionManifest = ::toml::from_str(input)?;
+    Ok(manifest.to_registry(known_types)?)
+}
+
+/// Upgrade a Toml string to the latest version of its types
+///
+/// The input is deserialized with versioning support and serialized
+/// with the version header `uris`.
+///
+/// # Generic Parameters
+/// - `T`: type of the document
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: Toml formatted string, first entry is the version header
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `uris`: The uris to serialize in the version header, usually the latest ones
+///
+/// # Returns
+/// The migrated string or the error that occurred
+pub fn migrate_str<T, VMR, AGG>(
+    input: &str,
+    resolver: &VMR,
+    aggregate: &AGG,
+    uris: &VersionGroupURIs,
+) -> Result<String, MigrateError>
+where
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM> + Serialize,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let value: T = deserialize(input, resolver, aggregate)?;
+    Ok(serialize(&value, uris)?)
+}
+
+/// Upgrade a Toml string to the latest version header of the registry
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// See `migrate_str`.
+pub fn migrate_str_latest<T, AGG>(
+    input: &str,
+    registry: &VersionRegistry,
+    aggregate: &AGG,
+) -> Result<String, MigrateError>
+where
+    T: for<'de> DeserializeVersioned<'de, DefaultVersionMap<'static>> + Serialize,
+    AGG: AggregateVersionMap,
+{
+    let uris = registry.latest_uris::<T>()?;
+    migrate_str::<T, _, _>(input, &registry.resolver(), aggregate, &uris)
+}
+
+/// Upgrade a Toml file in place to the latest version of its types
+///
+/// See `migrate_str`.
+///
+/// The file is replaced atomically, see `migrate::write_file_atomic`.
+///
+/// # Parameters
+/// - `path`: path of the Toml file
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+/// - `uris`: The uris to serialize in the version header, usually the latest ones
+/// - `dry_run`: when `true`, the file is not written. Use `Migration::diff` to report the changes.
+///
+/// # Returns
+/// The original and migrated documents or the error that occurred.
+/// The file is written only when the document changed.
+pub fn migrate_file<T, VMR, AGG>(
+    path: impl AsRef<Path>,
+    resolver: &VMR,
+    aggregate: &AGG,
+    uris: &VersionGroupURIs,
+    dry_run: bool,
+) -> Result<Migration, MigrateError>
+where
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM> + Serialize,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let path = path.as_ref();
+    let original = std::fs::read_to_string(path)?;
+    let migrated = migrate_str::<T, _, _>(&original, resolver, aggregate, uris)?;
+    let migration = Migration { original, migrated };
+
+    if !dry_run && migration.is_changed() {
+        write_file_atomic(path, migration.migrated.as_bytes())?;
+    }
+    Ok(migration)
+}
+
+/// Upgrade a Toml file in place to the latest version header of the registry
+///
+/// See `migrate_str_latest` and `migrate_file`.
+pub fn migrate_file_latest<T, AGG>(
+    path: impl AsRef<Path>,
+    registry: &VersionRegistry,
+    aggregate: &AGG,
+    dry_run: bool,
+) -> Result<Migration, MigrateError>
+where
+    T: for<'de> DeserializeVersioned<'de, DefaultVersionMap<'static>> + Serialize,
+    AGG: AggregateVersionMap,
+{
+    let uris = registry.latest_uris::<T>()?;
+    migrate_file::<T, _, _>(path, &registry.resolver(), aggregate, &uris, dry_run)
+}
@@ -1,8 +1,8 @@
 #[macro_export]
 macro_rules! impl_from_enum {
-    ($($enum:ident::$variant:ident => $from:ty),*,) => {
+    ($($enum:ident $(<$($param:ident $(: $bound:path)?),*>)?::$variant:ident => $from:ty),*,) => {
         $(
-        impl ::std::convert::From<$from> for $enum {
+        impl $(<$($param $(: $bound)?),*>)? ::std::convert::From<$from> for $enum $(<$($param),*>)? {
             fn from(v: $from) -> Self {
                 $enum::$variant(v)
             }
@@ -0,0 +1,187 @@
+//! Deserialization of already parsed value trees
+//!
+//! Documents merged from several layers are usually available as a value tree
+//! (`toml::Value`, `serde_json::Value`, `serde_yaml::Value` or `ron::Value`) rather than as a string.
+//! `deserialize_versioned_from_value` reads the version header from the root table
+//! of the tree and deserializes the rest with versioning support.
+//!
+//! Each value type is supported with its feature: `toml-support`, `json-support`, `yaml-support`
+//! and `ron-support`.
+
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, Error,
+    VersionGroupResolver, VersionGroupURIs, VersionMap, VersionedDeserializer,
+};
+use failure::Fail;
+use serde::de::Deserializer;
+use std::borrow::Borrow;
+
+/// Key of the version header in the root table of a document
+pub(crate) const VERSION_HEADER_KEY: &str = "v";
+
+/// A value tree that can contain a version header
+pub trait VersionedValue<'de>: Deserializer<'de> + Sized {
+    /// Remove an entry from the root table of the tree
+    ///
+    /// Returns `None` when the root is not a table or has no entry `key`.
+    fn take_entry(&mut self, key: &str) -> Option<Self>;
+
+    /// Remove the version header from the root table of the tree
+    ///
+    /// Returns `None` when the root is not a table or has no version header.
+    fn take_version_header(&mut self) -> Option<Self> {
+        self.take_entry(VERSION_HEADER_KEY)
+    }
+}
+
+/// Deserialization error of a value tree
+#[derive(Debug, Fail)]
+pub enum DeserializeValueError<E: Fail> {
+    /// The root of the tree is not a table with a version header
+    #[fail(display = "Missing version header 'v' in the root table")]
+    MissingVersionHeader,
+    /// The version header is invalid
+    #[fail(display = "Invalid version header: {}", _0)]
+    Header(E),
+    /// The tree can't be deserialized
+    #[fail(display = "{}", _0)]
+    De(Error<E>),
+    /// The version groups of the header can't be aggregated
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+}
+impl_from_enum! {
+    DeserializeValueError<E: Fail>::AggregateError => AggregateVersionMapError,
+}
+
+/// Deserialize a value tree with versioning support
+///
+/// The version header is removed from the root table of the tree,
+/// the remaining tree is deserialized with a `VersionedDeserializer`.
+///
+/// ```rust
+/// # #![feature(min_specialization)]
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # #[macro_use]
+/// # extern crate serde_version_derive;
+/// # use serde::Deserialize;
+/// # use serde_version::value::deserialize_versioned_from_value;
+/// #
+/// #[derive(Deserialize)]
+/// #[serde(rename = "A")]
+/// struct Av1 {
+///     a: u8,
+/// }
+///
+/// #[derive(Deserialize, DeserializeVersioned)]
+/// #[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+/// struct A {
+///     b: u8,
+/// }
+///
+/// impl From<Av1> for A {
+///     fn from(v: Av1) -> Self {
+///         Self { b: v.a }
+///     }
+/// }
+///
+/// version_group_resolver_static! {
+///     pub VERSIONS = {
+///         ("my.api_group", "1.0.0") => { A => 1, },
+///     }
+/// }
+///
+/// # fn main() {
+/// # #[cfg(feature = "json-support")]
+/// # {
+/// let value = serde_json::json!({ "v": ["my.api_group:1.0.0"], "a": 5 });
+/// let a: A = deserialize_versioned_from_value(value, &*VERSIONS, &()).unwrap();
+/// assert_eq!(5, a.b);
+/// # }
+/// # }
+/// ```
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `V`: type of the value tree
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `value`: the value tree, its root table contains the version header
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize_versioned_from_value<'de, T, V, VMR, AGG>(
+    mut value: V,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeValueError<V::Error>>
+where
+    V: VersionedValue<'de>,
+    V::Error: Fail,
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let header = value
+        .take_version_header()
+        .ok_or(DeserializeValueError::MissingVersionHeader)?;
+    let uris = <Vec<_> as serde::Deserialize>::deserialize(header)
+        .map_err(DeserializeValueError::Header)?;
+    let uris: VersionGroupURIs = uris.into();
+
+    let version_map = aggregate.aggregate_version_maps(&uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let de = VersionedDeserializer::new(value, version_map);
+    crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })
+    .map_err(|err| DeserializeValueError::De(err.reduce()))
+}
+
+#[cfg(feature = "toml-support")]
+impl<'de> VersionedValue<'de> for ::toml::Value {
+    fn take_entry(&mut self, key: &str) -> Option<Self> {
+        match self {
+            ::toml::Value::Table(table) => table.remove(key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "json-support")]
+impl<'de> VersionedValue<'de> for ::serde_json::Value {
+    fn take_entry(&mut self, key: &str) -> Option<Self> {
+        match self {
+            ::serde_json::Value::Object(map) => map.remove(key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "yaml-support")]
+impl<'de> VersionedValue<'de> for ::serde_yaml::Value {
+    fn take_entry(&mut self, key: &str) -> Option<Self> {
+        match self {
+            ::serde_yaml::Value::Mapping(mapping) => mapping.remove(key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "ron-support")]
+impl<'de> VersionedValue<'de> for ::ron::Value {
+    fn take_entry(&mut self, key: &str) -> Option<Self> {
+        match self {
+            ::ron::Value::Map(map) => map.remove(&::ron::Value::String(key.to_owned())),
+            _ => None,
+        }
+    }
+}
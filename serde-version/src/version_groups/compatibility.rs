@@ -0,0 +1,111 @@
+use super::{VersionManifestGroup, VersionSnapshot};
+use std::collections::HashMap;
+
+/// A breaking change between two snapshots
+///
+/// See `check_compatibility`.
+#[derive(Debug, Clone, PartialEq, Eq, Fail)]
+pub enum BreakingChange {
+    /// A version group was removed, files with this version uri can't be read anymore
+    #[fail(display = "The version uri {} was removed", uri)]
+    GroupRemoved { uri: String },
+    /// A version group was defined with different versions
+    #[fail(
+        display = "The version uri {} was redefined with different versions",
+        uri
+    )]
+    GroupRedefined { uri: String },
+    /// The latest version of a type changed, but no new version group uses it
+    #[fail(
+        display = "The latest version of the type {} changed from {} to {} without a new version group",
+        type_id, previous, current
+    )]
+    LatestVersionWithoutGroup {
+        type_id: String,
+        previous: usize,
+        current: usize,
+    },
+    /// A version of a type is not supported anymore, files using it can't be read anymore
+    #[fail(
+        display = "The version {} of the type {} was removed",
+        version, type_id
+    )]
+    VersionRemoved { type_id: String, version: usize },
+    /// A type is not versioned anymore, files using any of its versions can't be read anymore
+    #[fail(display = "The type {} was removed", type_id)]
+    TypeRemoved { type_id: String },
+}
+
+/// Find the breaking changes between the snapshot of a previous release and the current one
+///
+/// The version groups are compared by version uri, and the types by type id.
+/// The types added in the current snapshot are not compared,
+/// the types missing from the current snapshot are reported as removed.
+///
+/// The changes of the version groups are reported first, then the changes of the types.
+pub fn check_compatibility(
+    previous: &VersionSnapshot,
+    current: &VersionSnapshot,
+) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    let previous_groups = groups_by_uri(&previous.groups);
+    let current_groups = groups_by_uri(&current.groups);
+
+    for group in &previous.groups {
+        let uri = group.uri.to_string();
+        match current_groups.get(uri.as_str()) {
+            None => changes.push(BreakingChange::GroupRemoved { uri }),
+            Some(current_group) if current_group.types != group.types => {
+                changes.push(BreakingChange::GroupRedefined { uri })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (type_id, &current_latest) in &current.latest {
+        let previous_latest = match previous.latest.get(type_id) {
+            Some(&previous_latest) if previous_latest != current_latest => previous_latest,
+            _ => continue,
+        };
+        let has_new_group = current.groups.iter().any(|group| {
+            !previous_groups.contains_key(group.uri.to_string().as_str())
+                && group.types.get(type_id) == Some(&current_latest)
+        });
+        if !has_new_group {
+            changes.push(BreakingChange::LatestVersionWithoutGroup {
+                type_id: type_id.clone(),
+                previous: previous_latest,
+                current: current_latest,
+            });
+        }
+    }
+
+    for (type_id, previous_versions) in &previous.versions {
+        match current.versions.get(type_id) {
+            Some(current_versions) => changes.extend(
+                previous_versions
+                    .iter()
+                    .filter(|version| !current_versions.contains(version))
+                    .map(|&version| BreakingChange::VersionRemoved {
+                        type_id: type_id.clone(),
+                        version,
+                    }),
+            ),
+            None => changes.push(BreakingChange::TypeRemoved {
+                type_id: type_id.clone(),
+            }),
+        }
+    }
+
+    changes
+}
+
+fn groups_by_uri<'g, 'a>(
+    groups: &'g [VersionManifestGroup<'a>],
+) -> HashMap<String, &'g VersionManifestGroup<'a>> {
+    groups
+        .iter()
+        .map(|group| (group.uri.to_string(), group))
+        .collect()
+}
@@ -0,0 +1,240 @@
+use super::{VersionGroupResolver, VersionGroupURI, VersionGroupURIs, VersionRegistry};
+use crate::{DefaultVersionMap, VersionMap, VersionedTypeSet, VersionedTypes};
+use std::collections::HashSet;
+
+#[derive(Debug, Fail)]
+#[fail(
+    display = "No version group defines the version {} of the type {}",
+    version, type_id
+)]
+pub struct UncoveredType {
+    type_id: String,
+    version: usize,
+}
+
+/// The latest version of the versioned types reachable from `T`
+///
+/// See `VersionedTypes`.
+pub fn latest_version_map<T: ?Sized>() -> DefaultVersionMap<'static> {
+    let mut types = VersionedTypeSet::new();
+    T::versioned_types(&mut types);
+    types.into_version_map()
+}
+
+/// Find the version uris that define the latest version of all versioned types reachable from `T`
+///
+/// A version group is selected only if it defines the latest version of the reachable types it contains,
+/// and if it does not contain a reachable type already defined by a selected version group,
+/// so the version groups can be aggregated with the default `ConflictPolicy`.
+///
+/// The fewest version uris are returned, they are ordered by version uri. Among the selections with
+/// as few version uris, the first one in the order of the version uris is returned.
+///
+/// The selection is an exact cover, searched exhaustively: the search is exponential in the number
+/// of version groups in the worst case, but an application only defines a few version groups
+/// for a type tree.
+///
+/// # Parameters
+/// - `groups`: the current version groups
+///
+/// # Returns
+/// The version uris or the first type whose latest version is not defined by a version group
+pub fn latest_uris<'a, T, VM, I>(groups: I) -> Result<VersionGroupURIs<'a>, UncoveredType>
+where
+    T: ?Sized,
+    VM: VersionMap,
+    I: IntoIterator<Item = (VersionGroupURI<'a>, VM)>,
+{
+    let latest = latest_version_map::<T>();
+
+    // The reachable types of each compatible group
+    let mut candidates = groups
+        .into_iter()
+        .filter_map(|(uri, version_map)| {
+            let mut types = HashSet::new();
+            for (type_id, version) in version_map.iter() {
+                match latest.get_key_value(type_id) {
+                    Some((type_id, &latest_version)) if latest_version == version => {
+                        types.insert(*type_id);
+                    }
+                    Some(_) => return None,
+                    None => {}
+                }
+            }
+            if types.is_empty() {
+                None
+            } else {
+                Some((uri, types))
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(uri, _)| uri.to_string());
+
+    let mut types = latest.keys().copied().collect::<Vec<_>>();
+    types.sort_unstable();
+    let mut cover = Cover {
+        types: &types,
+        candidates: &candidates,
+        selected: Vec::new(),
+        covered: HashSet::new(),
+        best: None,
+        uncovered: None,
+    };
+    cover.search();
+
+    match cover.best {
+        Some(best) => {
+            // The candidates are sorted by version uri
+            let uris = candidates
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| best.contains(index))
+                .map(|(_, (uri, _))| uri)
+                .collect::<Vec<_>>();
+            Ok(uris.into())
+        }
+        None => {
+            let type_id = cover.uncovered.unwrap_or(types[0]);
+            Err(UncoveredType {
+                type_id: type_id.to_owned(),
+                version: latest[type_id],
+            })
+        }
+    }
+}
+
+/// Depth first search of the smallest exact cover of the types by the candidate version groups
+struct Cover<'c, 'a> {
+    /// The types to cover, sorted
+    types: &'c [&'static str],
+    /// The candidate version groups and their types, sorted by version uri
+    candidates: &'c [(VersionGroupURI<'a>, HashSet<&'static str>)],
+    /// The indices of the selected candidates
+    selected: Vec<usize>,
+    /// The types covered by the selected candidates
+    covered: HashSet<&'static str>,
+    /// The smallest cover found
+    best: Option<Vec<usize>>,
+    /// The first type that could not be covered
+    uncovered: Option<&'static str>,
+}
+
+impl<'c, 'a> Cover<'c, 'a> {
+    fn search(&mut self) {
+        let type_id = match self
+            .types
+            .iter()
+            .find(|type_id| !self.covered.contains(*type_id))
+        {
+            Some(type_id) => *type_id,
+            None => {
+                // The search is pruned below, so this cover is smaller than the best one:
+                // among the covers of the same size, the first one found is kept
+                self.best = Some(self.selected.clone());
+                return;
+            }
+        };
+        // A cover with another candidate can't be smaller than the best one
+        if let Some(best) = &self.best {
+            if self.selected.len() + 1 >= best.len() {
+                return;
+            }
+        }
+
+        // Each cover contains exactly one candidate with this type
+        let mut found = false;
+        for (index, (_, types)) in self.candidates.iter().enumerate() {
+            if types.contains(type_id) && types.is_disjoint(&self.covered) {
+                found = true;
+                self.selected.push(index);
+                self.covered.extend(types.iter().copied());
+                self.search();
+                for type_id in types {
+                    self.covered.remove(type_id);
+                }
+                self.selected.pop();
+            }
+        }
+        if !found && self.uncovered.is_none() {
+            self.uncovered = Some(type_id);
+        }
+    }
+}
+
+impl VersionRegistry {
+    /// Find the registered version uris that define the latest version
+    /// of all versioned types reachable from `T`
+    ///
+    /// See `latest_uris`.
+    pub fn latest_uris<T: ?Sized>(&self) -> Result<VersionGroupURIs<'static>, UncoveredType> {
+        let uris = self.uris();
+        let resolver = self.resolver();
+        latest_uris::<T, _, _>(uris.into_iter().filter_map(|uri| {
+            let version_map = resolver.resolve(&uri)?;
+            Some((uri, version_map))
+        }))
+    }
+}
+
+mod versioned_types_impls {
+    use crate::{VersionedTypeSet, VersionedTypes};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    macro_rules! impl_versioned_types {
+        ($(impl<$($param:ident),*> for $ty:ty => ($($visited:ident),*);)*) => {
+            $(
+                impl<$($param: ?Sized),*> VersionedTypes for $ty {
+                    fn versioned_types(types: &mut VersionedTypeSet) {
+                        $(<$visited as VersionedTypes>::versioned_types(types);)*
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_versioned_types! {
+        impl<T> for &T => (T);
+        impl<T> for &mut T => (T);
+        impl<T> for Box<T> => (T);
+        impl<T> for Rc<T> => (T);
+        impl<T> for Arc<T> => (T);
+    }
+
+    macro_rules! impl_versioned_types_sized {
+        ($(impl<$($param:ident),*> for $ty:ty => ($($visited:ident),*);)*) => {
+            $(
+                impl<$($param),*> VersionedTypes for $ty {
+                    fn versioned_types(types: &mut VersionedTypeSet) {
+                        $(<$visited as VersionedTypes>::versioned_types(types);)*
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_versioned_types_sized! {
+        impl<T> for [T] => (T);
+        impl<T> for Option<T> => (T);
+        impl<T> for Vec<T> => (T);
+        impl<T> for VecDeque<T> => (T);
+        impl<T> for LinkedList<T> => (T);
+        impl<T> for BTreeSet<T> => (T);
+        impl<T, S> for HashSet<T, S> => (T);
+        impl<K, V> for BTreeMap<K, V> => (K, V);
+        impl<K, V, S> for HashMap<K, V, S> => (K, V);
+        impl<T0> for (T0,) => (T0);
+        impl<T0, T1> for (T0, T1) => (T0, T1);
+        impl<T0, T1, T2> for (T0, T1, T2) => (T0, T1, T2);
+        impl<T0, T1, T2, T3> for (T0, T1, T2, T3) => (T0, T1, T2, T3);
+        impl<T0, T1, T2, T3, T4> for (T0, T1, T2, T3, T4) => (T0, T1, T2, T3, T4);
+        impl<T0, T1, T2, T3, T4, T5> for (T0, T1, T2, T3, T4, T5) => (T0, T1, T2, T3, T4, T5);
+    }
+
+    impl<T, const N: usize> VersionedTypes for [T; N] {
+        fn versioned_types(types: &mut VersionedTypeSet) {
+            T::versioned_types(types);
+        }
+    }
+}
@@ -80,6 +80,72 @@ macro_rules! __version_map_static {
     }
 }
 
+/// Declare version groups registered by `VersionRegistry::register_static_groups`
+///
+/// Each crate can declare its version groups, they are collected at link time.
+/// Requires the `inventory-support` feature.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # use serde_version::{VersionGroupURI, VersionRegistry};
+/// # use std::convert::TryFrom;
+/// #
+/// # struct A;
+/// # struct B;
+/// register_version_group! {
+///     ( "my.plugin", "1.0.0" ) => { A => 2, B => 3, },
+/// }
+///
+/// # fn main() {
+/// let registry = VersionRegistry::new();
+/// registry.register_static_groups().unwrap();
+/// assert!(registry.contains(&VersionGroupURI::try_from("my.plugin:1.0.0").unwrap()));
+/// # }
+/// ```
+#[cfg(feature = "inventory-support")]
+#[macro_export]
+macro_rules! register_version_group {
+    ($(($api_group:expr, $api_version:expr) => { $($path:path => $version:expr),*, }),*,) => {
+        $(
+            $crate::inventory::submit! {
+                $crate::StaticVersionGroup {
+                    api_group: $api_group,
+                    version: $api_version,
+                    version_map: {
+                        fn version_map() -> $crate::DefaultVersionMap<'static> {
+                            version_map_new! { $($path => $version),*, }
+                        }
+                        version_map
+                    },
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "inventory-support")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_versioned_type {
+    ($ty:ty) => {
+        $crate::inventory::submit! {
+            $crate::StaticVersionedType {
+                type_id: ::std::any::type_name::<$ty>,
+                latest_version: <$ty as $crate::Versioned>::LATEST_VERSION,
+                versions: <$ty as $crate::Versioned>::VERSIONS,
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "inventory-support"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_versioned_type {
+    ($ty:ty) => {};
+}
+
 /// Instantiate a `VersionGroupResolver` with specified version groups
 ///
 /// ```rust
@@ -140,6 +206,35 @@ macro_rules! version_map_new {
     };
 }
 
+/// Instantiate `KnownTypes` with specified types
+///
+/// A type can be known by a stable alias too, with `Type as "alias"`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// #
+/// # struct A;
+/// # struct B;
+/// # // To have extern crate syntax
+/// # fn main() {
+/// let known_types = known_types! { A, B as "my.B", };
+/// # }
+/// ```
+#[macro_export]
+macro_rules! known_types {
+    ($($path:path $(as $alias:literal)?),*,) => {
+        {
+            let mut known_types = $crate::KnownTypes::new();
+            $(
+                known_types.insert::<$path>();
+                $(known_types.insert_alias::<$path>($alias);)?
+            )*
+            known_types
+        }
+    };
+}
+
 /// Create an enum that maps an entry to a `VersionGroupURI<T>`
 ///
 /// ```rust
@@ -0,0 +1,183 @@
+use super::{RegisterVersionGroupError, VersionGroupURI, VersionRegistry};
+use crate::DefaultVersionMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Fail)]
+#[fail(
+    display = "The type {} of version uri {} is unknown to this binary",
+    type_id, uri
+)]
+pub struct UnknownTypeId {
+    type_id: String,
+    uri: VersionGroupURI<'static>,
+}
+
+#[derive(Debug, Fail)]
+pub enum ManifestError {
+    #[fail(display = "{}", _0)]
+    UnknownTypeId(UnknownTypeId),
+    #[fail(display = "{}", _0)]
+    Register(RegisterVersionGroupError),
+}
+impl_from_enum! {
+    ManifestError::UnknownTypeId => UnknownTypeId,
+    ManifestError::Register => RegisterVersionGroupError,
+}
+
+/// The type ids known to the binary
+///
+/// A type id is the name given by `std::any::type_name`.
+///
+/// The output of `std::any::type_name` is not guaranteed to be stable between compiler versions.
+/// A manifest shipped separately from the binary can use a stable alias instead,
+/// registered with `insert_alias` or `known_types! { A as "my.A", }`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// #
+/// # struct A;
+/// # struct B;
+/// # fn main() {
+/// let known_types = known_types! { A, B as "my.B", };
+/// assert!(known_types.contains(std::any::type_name::<A>()));
+/// assert!(known_types.contains("my.B"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownTypes {
+    type_ids: HashSet<&'static str>,
+    aliases: HashMap<&'static str, &'static str>,
+}
+
+impl KnownTypes {
+    /// Create an empty set of type ids
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the type id of `T`
+    pub fn insert<T: ?Sized>(&mut self) {
+        self.type_ids.insert(std::any::type_name::<T>());
+    }
+
+    /// Add `alias` as a stable type id of `T`
+    ///
+    /// The type id of `T` is added too.
+    pub fn insert_alias<T: ?Sized>(&mut self, alias: &'static str) {
+        self.insert::<T>();
+        self.aliases.insert(alias, std::any::type_name::<T>());
+    }
+
+    /// Whether `type_id` is known, either as a type id or as an alias
+    pub fn contains(&self, type_id: &str) -> bool {
+        self.get(type_id).is_some()
+    }
+
+    /// The alias of the type id `type_name`, if any
+    pub fn alias(&self, type_name: &str) -> Option<&'static str> {
+        self.aliases
+            .iter()
+            .find(|(_, aliased)| **aliased == type_name)
+            .map(|(alias, _)| *alias)
+    }
+
+    fn get(&self, type_id: &str) -> Option<&'static str> {
+        self.type_ids
+            .get(type_id)
+            .or_else(|| self.aliases.get(type_id))
+            .copied()
+    }
+}
+
+impl std::iter::FromIterator<&'static str> for KnownTypes {
+    fn from_iter<T: IntoIterator<Item = &'static str>>(iter: T) -> Self {
+        Self {
+            type_ids: iter.into_iter().collect(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+/// Version groups defined in a manifest file
+///
+/// It lets the version groups be shipped without recompiling the binary.
+///
+/// In Toml:
+/// ```toml
+/// [[groups]]
+/// uri = "my.api_group:1.0.0"
+/// types = { "my_crate::A" = 2, "my_crate::B" = 3 }
+///
+/// [[groups]]
+/// uri = "my.api_group:1.1.0"
+/// types = { "my_crate::A" = 3, "my_crate::B" = 3 }
+/// ```
+///
+/// The type ids are the names given by `std::any::type_name`, or the aliases
+/// of `KnownTypes`. Prefer aliases when the manifest outlives the compiler version
+/// of the binary: the names of `std::any::type_name` may change between compiler versions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct VersionManifest<'a> {
+    #[serde(borrow, default)]
+    pub groups: Vec<VersionManifestGroup<'a>>,
+}
+
+/// A version group defined in a manifest file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VersionManifestGroup<'a> {
+    #[serde(borrow)]
+    pub uri: VersionGroupURI<'a>,
+    /// The version of each type id
+    #[serde(default)]
+    pub types: BTreeMap<String, usize>,
+}
+
+impl<'a> VersionManifest<'a> {
+    /// Register the version groups of the manifest in `registry`
+    ///
+    /// All type ids must be in `known_types`, and all the version groups must be registrable,
+    /// otherwise nothing is registered.
+    pub fn register(
+        &self,
+        registry: &VersionRegistry,
+        known_types: &KnownTypes,
+    ) -> Result<(), ManifestError> {
+        let groups = self
+            .groups
+            .iter()
+            .map(|group| Ok((&group.uri, group.version_map(known_types)?)))
+            .collect::<Result<Vec<_>, UnknownTypeId>>()?;
+
+        registry.register_all(groups)?;
+        Ok(())
+    }
+
+    /// Create a registry with the version groups of the manifest
+    ///
+    /// All type ids must be in `known_types`.
+    pub fn to_registry(&self, known_types: &KnownTypes) -> Result<VersionRegistry, ManifestError> {
+        let registry = VersionRegistry::new();
+        self.register(&registry, known_types)?;
+        Ok(registry)
+    }
+}
+
+impl<'a> VersionManifestGroup<'a> {
+    fn version_map(
+        &self,
+        known_types: &KnownTypes,
+    ) -> Result<DefaultVersionMap<'static>, UnknownTypeId> {
+        self.types
+            .iter()
+            .map(|(type_id, version)| match known_types.get(type_id) {
+                Some(type_id) => Ok((type_id, *version)),
+                None => Err(UnknownTypeId {
+                    type_id: type_id.clone(),
+                    uri: self.uri.to_static(),
+                }),
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,28 @@
+mod compatibility;
+mod latest;
 mod macros;
+mod manifest;
+mod registry;
 mod resolver;
+mod snapshot;
 mod uri;
 
-pub use resolver::{DefaultVersionGroupResolver, VersionGroupResolver};
-pub use uri::{VersionGroupURI, VersionGroupURIs};
+pub use compatibility::{check_compatibility, BreakingChange};
+pub use latest::{latest_uris, latest_version_map, UncoveredType};
+pub use manifest::{
+    KnownTypes, ManifestError, UnknownTypeId, VersionManifest, VersionManifestGroup,
+};
+#[cfg(feature = "inventory-support")]
+pub use registry::StaticVersionGroup;
+pub use registry::{
+    RegisterVersionGroupError, RegistryResolver, TypeInMultipleApiGroups, TypeVersionMismatch,
+    VersionGroupAlreadyRegistered, VersionRegistry,
+};
+pub use resolver::{
+    ChainResolver, DefaultVersionGroupResolver, DynVersionGroupResolver, MapResolver, OrResolver,
+    VersionGroupResolver,
+};
+#[cfg(feature = "inventory-support")]
+pub use snapshot::StaticVersionedType;
+pub use snapshot::VersionSnapshot;
+pub use uri::{TryFromError, VersionGroupURI, VersionGroupURIs};
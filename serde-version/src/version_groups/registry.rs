@@ -0,0 +1,348 @@
+use super::uri::TryFromError;
+use super::{VersionGroupResolver, VersionGroupURI};
+use crate::{ConflictPolicy, DefaultVersionMap};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[derive(Debug, Fail)]
+#[fail(display = "Version uri {} is already registered", uri)]
+pub struct VersionGroupAlreadyRegistered {
+    uri: VersionGroupURI<'static>,
+}
+
+#[derive(Debug, Fail)]
+#[fail(
+    display = "The type {} of version uri {} is already registered by the api group {}",
+    type_name, uri, api_group
+)]
+pub struct TypeInMultipleApiGroups {
+    type_name: String,
+    uri: VersionGroupURI<'static>,
+    api_group: String,
+}
+
+#[derive(Debug, Fail)]
+#[fail(
+    display = "The type {} is defined with the version {} by version uri {} and the version {} by version uri {}",
+    type_name, version, uri, registered_version, registered_uri
+)]
+pub struct TypeVersionMismatch {
+    type_name: String,
+    version: usize,
+    uri: VersionGroupURI<'static>,
+    registered_version: usize,
+    registered_uri: VersionGroupURI<'static>,
+}
+
+#[derive(Debug, Fail)]
+pub enum RegisterVersionGroupError {
+    #[fail(display = "{}", _0)]
+    VersionGroupAlreadyRegistered(VersionGroupAlreadyRegistered),
+    #[fail(display = "{}", _0)]
+    TypeInMultipleApiGroups(TypeInMultipleApiGroups),
+    #[fail(display = "{}", _0)]
+    TypeVersionMismatch(TypeVersionMismatch),
+    #[fail(display = "{}", _0)]
+    InvalidURI(TryFromError),
+}
+impl_from_enum! {
+    RegisterVersionGroupError::VersionGroupAlreadyRegistered => VersionGroupAlreadyRegistered,
+    RegisterVersionGroupError::TypeInMultipleApiGroups => TypeInMultipleApiGroups,
+    RegisterVersionGroupError::TypeVersionMismatch => TypeVersionMismatch,
+    RegisterVersionGroupError::InvalidURI => TryFromError,
+}
+
+type ApiGroupVersions = HashMap<String, Arc<DefaultVersionMap<'static>>>;
+type ApiGroups = HashMap<String, ApiGroupVersions>;
+
+/// A thread-safe registry where version groups are registered at runtime
+///
+/// Use it when the version groups are not all known at compile time,
+/// for instance when they are provided by plugins.
+/// Use `resolver` to resolve the registered version groups.
+///
+/// Registration fails when:
+/// - the version uri is already registered with a different version map
+/// - a type is already registered by another api group and the `ConflictPolicy` of the registry
+///   does not allow it, see `with_conflict_policy`. With the default `ConflictPolicy::AllowEqual`,
+///   the api groups must register a shared type with the same version.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # use serde_version::{VersionGroupResolver, VersionGroupURI, VersionRegistry};
+/// # use std::convert::TryFrom;
+/// #
+/// # struct A;
+/// # fn main() {
+/// let registry = VersionRegistry::new();
+/// let uri = VersionGroupURI::try_from("my.plugin:1.0.0").unwrap();
+/// registry.register(&uri, version_map_new! { A => 2, }).unwrap();
+/// assert!(registry.resolver().resolve(&uri).is_some());
+///
+/// registry.unregister(&uri);
+/// assert!(registry.resolver().resolve(&uri).is_none());
+/// # }
+/// ```
+pub struct VersionRegistry {
+    api_groups: RwLock<ApiGroups>,
+    conflict_policy: ConflictPolicy,
+}
+
+impl Default for VersionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VersionRegistry {
+    /// Create an empty registry
+    ///
+    /// Its conflict policy is `ConflictPolicy::AllowEqual`.
+    pub fn new() -> Self {
+        Self::with_conflict_policy(ConflictPolicy::AllowEqual)
+    }
+
+    /// Create an empty registry checking the types registered by several api groups with `policy`
+    ///
+    /// - `ConflictPolicy::Error`: a type can be registered by a single api group
+    /// - `ConflictPolicy::AllowEqual`: a type can be registered by several api groups
+    ///   with the same version, so plugins share common types
+    /// - other policies: a type can be registered by several api groups with any version,
+    ///   the version groups are aggregated with the same policy to select the version
+    pub fn with_conflict_policy(policy: ConflictPolicy) -> Self {
+        Self {
+            api_groups: RwLock::default(),
+            conflict_policy: policy,
+        }
+    }
+
+    /// Register the version map of a version uri
+    ///
+    /// Registering the same version map again does nothing.
+    pub fn register(
+        &self,
+        uri: &VersionGroupURI,
+        version_map: DefaultVersionMap<'static>,
+    ) -> Result<(), RegisterVersionGroupError> {
+        self.insert(&mut self.write(), uri, version_map)
+    }
+
+    /// Register the version maps of several version uris
+    ///
+    /// Either all the version groups are registered or none: when a version group can't be
+    /// registered, the registry is left unchanged. The version groups are checked against
+    /// the registered ones and against each other.
+    pub fn register_all<'g, 'u: 'g, I>(&self, groups: I) -> Result<(), RegisterVersionGroupError>
+    where
+        I: IntoIterator<Item = (&'g VersionGroupURI<'u>, DefaultVersionMap<'static>)>,
+    {
+        let mut api_groups = self.write();
+        // The version maps are shared, only the maps of the api groups are copied
+        let mut staged = api_groups.clone();
+        for (uri, version_map) in groups {
+            self.insert(&mut staged, uri, version_map)?;
+        }
+        *api_groups = staged;
+        Ok(())
+    }
+
+    fn insert(
+        &self,
+        api_groups: &mut ApiGroups,
+        uri: &VersionGroupURI,
+        version_map: DefaultVersionMap<'static>,
+    ) -> Result<(), RegisterVersionGroupError> {
+        if let Some(registered) = api_groups
+            .get(uri.api_group())
+            .and_then(|versions| versions.get(uri.version()))
+        {
+            return if **registered == version_map {
+                Ok(())
+            } else {
+                Err(VersionGroupAlreadyRegistered {
+                    uri: uri.to_static(),
+                }
+                .into())
+            };
+        }
+
+        self.check_conflicts(api_groups, uri, &version_map)?;
+
+        api_groups
+            .entry(uri.api_group().to_owned())
+            .or_default()
+            .insert(uri.version().to_owned(), Arc::new(version_map));
+        Ok(())
+    }
+
+    /// Unregister a version uri
+    ///
+    /// Returns whether the version uri was registered.
+    /// Its version map is freed once the `Arc` returned by `get` are dropped.
+    pub fn unregister(&self, uri: &VersionGroupURI) -> bool {
+        let mut api_groups = self.write();
+        let removed = match api_groups.get_mut(uri.api_group()) {
+            Some(versions) => versions.remove(uri.version()).is_some(),
+            None => false,
+        };
+        if api_groups
+            .get(uri.api_group())
+            .is_some_and(HashMap::is_empty)
+        {
+            api_groups.remove(uri.api_group());
+        }
+        removed
+    }
+
+    /// Whether a version uri is registered
+    pub fn contains(&self, uri: &VersionGroupURI) -> bool {
+        self.get(uri).is_some()
+    }
+
+    /// The registered version uris
+    pub fn uris(&self) -> Vec<VersionGroupURI<'static>> {
+        let api_groups = self.read();
+        let mut uris = api_groups
+            .iter()
+            .flat_map(|(api_group, versions)| {
+                versions.keys().map(move |version| {
+                    VersionGroupURI::try_from(format!("{}:{}", api_group, version))
+                        .expect("Registered version uris are valid")
+                })
+            })
+            .collect::<Vec<_>>();
+        uris.sort_by_key(ToString::to_string);
+        uris
+    }
+
+    /// The version map registered for a version uri
+    pub fn get(&self, uri: &VersionGroupURI) -> Option<Arc<DefaultVersionMap<'static>>> {
+        self.read()
+            .get(uri.api_group())
+            .and_then(|versions| versions.get(uri.version()))
+            .cloned()
+    }
+
+    /// A resolver of the registered version groups
+    ///
+    /// The resolver holds a read lock on the registry: registering or unregistering
+    /// a version group on the same thread while it is alive deadlocks.
+    pub fn resolver(&self) -> RegistryResolver<'_> {
+        RegistryResolver {
+            api_groups: self.read(),
+        }
+    }
+
+    /// Check the types of `version_map` registered by other api groups with the conflict policy
+    fn check_conflicts(
+        &self,
+        api_groups: &ApiGroups,
+        uri: &VersionGroupURI,
+        version_map: &DefaultVersionMap<'static>,
+    ) -> Result<(), RegisterVersionGroupError> {
+        for (api_group, versions) in api_groups.iter() {
+            if api_group == uri.api_group() {
+                continue;
+            }
+            for (registered_version_name, registered) in versions.iter() {
+                for (type_name, version) in version_map.iter() {
+                    let registered_version = match registered.get(type_name) {
+                        Some(registered_version) => *registered_version,
+                        None => continue,
+                    };
+                    if self.conflict_policy.allows(registered_version, *version) {
+                        continue;
+                    }
+                    return Err(match self.conflict_policy {
+                        ConflictPolicy::Error => TypeInMultipleApiGroups {
+                            type_name: (*type_name).to_owned(),
+                            uri: uri.to_static(),
+                            api_group: api_group.clone(),
+                        }
+                        .into(),
+                        _ => TypeVersionMismatch {
+                            type_name: (*type_name).to_owned(),
+                            version: *version,
+                            uri: uri.to_static(),
+                            registered_version,
+                            registered_uri: VersionGroupURI::try_from(format!(
+                                "{}:{}",
+                                api_group, registered_version_name
+                            ))
+                            .expect("Registered version uris are valid"),
+                        }
+                        .into(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ApiGroups> {
+        // The registry is always in a valid state, even if a thread panicked
+        self.api_groups
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ApiGroups> {
+        self.api_groups
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Resolve the version groups registered in a `VersionRegistry`
+///
+/// Use `VersionRegistry::resolver` to build it.
+pub struct RegistryResolver<'r> {
+    api_groups: RwLockReadGuard<'r, ApiGroups>,
+}
+
+impl<'r> VersionGroupResolver for RegistryResolver<'r> {
+    type VM = DefaultVersionMap<'static>;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        let uri = uri.borrow();
+        self.api_groups
+            .get(uri.api_group())
+            .and_then(|versions| versions.get(uri.version()))
+            .map(|version_map| &**version_map)
+    }
+}
+
+/// A version group declared with `register_version_group!`
+///
+/// Use `VersionRegistry::register_static_groups` to register all the declared version groups.
+#[cfg(feature = "inventory-support")]
+pub struct StaticVersionGroup {
+    #[doc(hidden)]
+    pub api_group: &'static str,
+    #[doc(hidden)]
+    pub version: &'static str,
+    #[doc(hidden)]
+    pub version_map: fn() -> DefaultVersionMap<'static>,
+}
+
+#[cfg(feature = "inventory-support")]
+inventory::collect!(StaticVersionGroup);
+
+#[cfg(feature = "inventory-support")]
+impl VersionRegistry {
+    /// Register all the version groups declared with `register_version_group!`
+    /// in the crates linked to the program
+    ///
+    /// Fails on the first version group that can't be registered,
+    /// including the version groups with an invalid api group or version.
+    pub fn register_static_groups(&self) -> Result<(), RegisterVersionGroupError> {
+        for group in inventory::iter::<StaticVersionGroup> {
+            let uri = VersionGroupURI::try_from(format!("{}:{}", group.api_group, group.version))?;
+            self.register(&uri, (group.version_map)())?;
+        }
+        Ok(())
+    }
+}
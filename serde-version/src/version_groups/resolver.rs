@@ -2,6 +2,7 @@ use super::VersionGroupURI;
 use crate::DefaultVersionMap;
 use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 /// Find the `VersionMap` associated to a `VersionGroupURI`
 pub trait VersionGroupResolver {
@@ -9,10 +10,33 @@ pub trait VersionGroupResolver {
     type VM;
 
     /// Find the `VersionMap` associated to a `VersionGroupURI`
-    fn resolve<'s, 'u: 's, T: Borrow<VersionGroupURI<'u>> + 's>(
-        &'s self,
-        uri: &'u T,
-    ) -> Option<&Self::VM>;
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM>;
+
+    /// Use `other` when a version uri is not found by this resolver
+    fn or<R>(self, other: R) -> OrResolver<Self, R>
+    where
+        Self: Sized,
+        R: VersionGroupResolver<VM = Self::VM>,
+    {
+        OrResolver {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Rewrite the version uris before resolving them
+    ///
+    /// See `MapResolver`.
+    fn map_uri<F>(self, map: F) -> MapResolver<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&VersionGroupURI) -> Option<VersionGroupURI<'static>>,
+    {
+        MapResolver {
+            resolver: self,
+            map,
+        }
+    }
 }
 
 pub type DefaultVersionGroupResolver<'a> = HashMap<(&'a str, &'a str), Box<DefaultVersionMap<'a>>>;
@@ -20,12 +44,240 @@ pub type DefaultVersionGroupResolver<'a> = HashMap<(&'a str, &'a str), Box<Defau
 impl<'a> VersionGroupResolver for DefaultVersionGroupResolver<'a> {
     type VM = DefaultVersionMap<'a>;
 
-    fn resolve<'s, 'u: 's, T: Borrow<VersionGroupURI<'u>> + 's>(
-        &'s self,
-        uri: &'u T,
-    ) -> Option<&Self::VM> {
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        let uri = uri.borrow();
+        let key = (uri.api_group(), uri.version());
+        self.get(&key as &dyn VersionGroupKey)
+            .map(|version_map| &**version_map)
+    }
+}
+
+/// Key of a `DefaultVersionGroupResolver` that can be borrowed for a shorter lifetime
+///
+/// The keys of the map are `(&'a str, &'a str)`, looking up with a tuple requires a version uri
+/// that outlives the resolver.
+/// Both are borrowed as a `dyn VersionGroupKey` instead, which hashes like the tuple.
+trait VersionGroupKey {
+    fn api_group(&self) -> &str;
+    fn version(&self) -> &str;
+}
+
+impl<'a> VersionGroupKey for (&'a str, &'a str) {
+    fn api_group(&self) -> &str {
+        self.0
+    }
+    fn version(&self) -> &str {
+        self.1
+    }
+}
+
+impl<'a, 'b> Borrow<dyn VersionGroupKey + 'b> for (&'a str, &'a str)
+where
+    'a: 'b,
+{
+    fn borrow(&self) -> &(dyn VersionGroupKey + 'b) {
+        self
+    }
+}
+
+impl Hash for dyn VersionGroupKey + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.api_group().hash(state);
+        self.version().hash(state);
+    }
+}
+
+impl PartialEq for dyn VersionGroupKey + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.api_group() == other.api_group() && self.version() == other.version()
+    }
+}
+
+impl Eq for dyn VersionGroupKey + '_ {}
+
+impl<R: VersionGroupResolver + ?Sized> VersionGroupResolver for &R {
+    type VM = R::VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        (**self).resolve(uri)
+    }
+}
+
+impl<R: VersionGroupResolver + ?Sized> VersionGroupResolver for Box<R> {
+    type VM = R::VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        (**self).resolve(uri)
+    }
+}
+
+/// Object safe version of `VersionGroupResolver`
+///
+/// It is implemented for all `VersionGroupResolver`,
+/// use `Box<dyn DynVersionGroupResolver<VM>>` to combine different types of resolvers.
+pub trait DynVersionGroupResolver<VM> {
+    /// Find the `VersionMap` associated to a `VersionGroupURI`
+    fn resolve_dyn(&self, uri: &VersionGroupURI) -> Option<&VM>;
+}
+
+impl<R: VersionGroupResolver> DynVersionGroupResolver<R::VM> for R {
+    fn resolve_dyn(&self, uri: &VersionGroupURI) -> Option<&R::VM> {
+        self.resolve(uri)
+    }
+}
+
+impl<'a, VM> VersionGroupResolver for dyn DynVersionGroupResolver<VM> + 'a {
+    type VM = VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        self.resolve_dyn(uri.borrow())
+    }
+}
+
+impl<'a, VM> VersionGroupResolver for dyn DynVersionGroupResolver<VM> + Send + Sync + 'a {
+    type VM = VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        self.resolve_dyn(uri.borrow())
+    }
+}
+
+/// Resolve with `first`, then with `second` if the version uri was not found
+///
+/// Use `VersionGroupResolver::or` to build it.
+pub struct OrResolver<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> VersionGroupResolver for OrResolver<A, B>
+where
+    A: VersionGroupResolver,
+    B: VersionGroupResolver<VM = A::VM>,
+{
+    type VM = A::VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        self.first.resolve(uri).or_else(|| self.second.resolve(uri))
+    }
+}
+
+/// Resolve with each resolver in order, until the version uri is found
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # use serde_version::{ChainResolver, DefaultVersionMap, DynVersionGroupResolver};
+/// #
+/// # struct A;
+/// # struct B;
+/// # fn main() {
+/// let core = version_group_resolver_new! {
+///     ( "my.api_group", "1.0.0" ) => { A => 2, },
+/// };
+/// let plugin = version_group_resolver_new! {
+///     ( "my.plugin", "1.0.0" ) => { B => 3, },
+/// };
+///
+/// let mut resolver: ChainResolver<Box<dyn DynVersionGroupResolver<DefaultVersionMap>>> =
+///     ChainResolver::new();
+/// resolver.push(Box::new(core));
+/// resolver.push(Box::new(plugin));
+/// # }
+/// ```
+pub struct ChainResolver<R> {
+    resolvers: Vec<R>,
+}
+
+impl<R> ChainResolver<R> {
+    /// Create an empty `ChainResolver`
+    pub fn new() -> Self {
+        Self {
+            resolvers: Vec::new(),
+        }
+    }
+
+    /// Add a resolver, used after the ones previously added
+    pub fn push(&mut self, resolver: R) {
+        self.resolvers.push(resolver);
+    }
+
+    /// The resolvers, in the order they are used
+    pub fn resolvers(&self) -> &[R] {
+        &self.resolvers
+    }
+}
+
+impl<R> Default for ChainResolver<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> From<Vec<R>> for ChainResolver<R> {
+    fn from(resolvers: Vec<R>) -> Self {
+        Self { resolvers }
+    }
+}
+
+impl<R: VersionGroupResolver> VersionGroupResolver for ChainResolver<R> {
+    type VM = R::VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
+        self.resolvers
+            .iter()
+            .find_map(|resolver| resolver.resolve(uri))
+    }
+}
+
+/// Rewrite the version uris before resolving them
+///
+/// When `map` returns a version uri, it is resolved instead of the original one.
+/// Otherwise, the original version uri is resolved.
+///
+/// Use `VersionGroupResolver::map_uri` to build it.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # use serde_version::{VersionGroupResolver, VersionGroupURI};
+/// # use std::convert::TryFrom;
+/// #
+/// # struct A;
+/// # fn main() {
+/// let resolver = version_group_resolver_new! {
+///     ( "my.api_group", "1.0.0" ) => { A => 2, },
+/// }
+/// .map_uri(|uri| {
+///     // 'my.old_api_group' was renamed to 'my.api_group'
+///     if uri.api_group() == "my.old_api_group" {
+///         VersionGroupURI::try_from(format!("my.api_group:{}", uri.version())).ok()
+///     } else {
+///         None
+///     }
+/// });
+///
+/// let uri = VersionGroupURI::try_from("my.old_api_group:1.0.0").unwrap();
+/// assert!(resolver.resolve(&uri).is_some());
+/// # }
+/// ```
+pub struct MapResolver<R, F> {
+    resolver: R,
+    map: F,
+}
+
+impl<R, F> VersionGroupResolver for MapResolver<R, F>
+where
+    R: VersionGroupResolver,
+    F: Fn(&VersionGroupURI) -> Option<VersionGroupURI<'static>>,
+{
+    type VM = R::VM;
+
+    fn resolve<'u, T: Borrow<VersionGroupURI<'u>>>(&self, uri: &T) -> Option<&Self::VM> {
         let uri = uri.borrow();
-        self.get(&(uri.api_group(), uri.version()))
-            .map(std::ops::Deref::deref)
+        match (self.map)(uri) {
+            Some(mapped) => self.resolver.resolve(&mapped),
+            None => self.resolver.resolve(uri),
+        }
     }
 }
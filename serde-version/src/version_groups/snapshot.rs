@@ -0,0 +1,157 @@
+use super::{KnownTypes, VersionGroupURI, VersionManifestGroup, VersionRegistry};
+use crate::{VersionMap, Versioned};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A snapshot of the version groups and of the latest version of the types
+///
+/// Serialize it to a machine-readable file and compare it with the one of the
+/// previous release, to find the types whose latest version changed without a new version group.
+///
+/// The groups have the format of a `VersionManifest`, so a snapshot can be loaded as a manifest.
+///
+/// The type ids are the names given by `std::any::type_name`, which may change between
+/// compiler versions: compare snapshots generated with the same toolchain, or replace
+/// the type ids by stable aliases with `use_aliases`.
+///
+/// ```rust
+/// # #![feature(min_specialization)]
+/// # #[macro_use]
+/// # extern crate serde_version;
+/// # #[macro_use]
+/// # extern crate serde_version_derive;
+/// # use serde::Deserialize;
+/// # use serde_version::{VersionGroupURI, VersionRegistry};
+/// # use std::convert::TryFrom;
+/// #
+/// #[derive(Deserialize, DeserializeVersioned)]
+/// #[versions(v(index = 1, self))]
+/// struct A;
+///
+/// # fn main() {
+/// let registry = VersionRegistry::new();
+/// let uri = VersionGroupURI::try_from("my.api_group:1.0.0").unwrap();
+/// registry.register(&uri, version_map_new! { A => 1, }).unwrap();
+///
+/// let mut snapshot = registry.snapshot();
+/// snapshot.insert_type::<A>();
+/// assert_eq!(Some(&1), snapshot.latest.get(std::any::type_name::<A>()));
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct VersionSnapshot<'a> {
+    /// The version groups, ordered by version uri
+    #[serde(borrow, default)]
+    pub groups: Vec<VersionManifestGroup<'a>>,
+    /// The latest version of each type id
+    #[serde(default)]
+    pub latest: BTreeMap<String, usize>,
+    /// The versions supported for each type id, in ascending order
+    #[serde(default)]
+    pub versions: BTreeMap<String, Vec<usize>>,
+}
+
+impl<'a> VersionSnapshot<'a> {
+    /// Create an empty snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a version group
+    ///
+    /// A version group already in the snapshot is replaced.
+    pub fn insert_group<VM: VersionMap>(&mut self, uri: VersionGroupURI<'a>, version_map: &VM) {
+        let group = VersionManifestGroup {
+            types: version_map
+                .iter()
+                .map(|(type_id, version)| (type_id.to_owned(), version))
+                .collect(),
+            uri,
+        };
+
+        let uri = group.uri.to_string();
+        match self
+            .groups
+            .binary_search_by(|group| group.uri.to_string().cmp(&uri))
+        {
+            Ok(index) => self.groups[index] = group,
+            Err(index) => self.groups.insert(index, group),
+        }
+    }
+
+    /// Replace the type ids by their alias in `known_types`
+    ///
+    /// The type ids without alias are kept.
+    pub fn use_aliases(&mut self, known_types: &KnownTypes) {
+        let alias = |type_id: String| match known_types.alias(&type_id) {
+            Some(alias) => alias.to_owned(),
+            None => type_id,
+        };
+        for group in &mut self.groups {
+            group.types = std::mem::take(&mut group.types)
+                .into_iter()
+                .map(|(type_id, version)| (alias(type_id), version))
+                .collect();
+        }
+        self.latest = std::mem::take(&mut self.latest)
+            .into_iter()
+            .map(|(type_id, version)| (alias(type_id), version))
+            .collect();
+        self.versions = std::mem::take(&mut self.versions)
+            .into_iter()
+            .map(|(type_id, versions)| (alias(type_id), versions))
+            .collect();
+    }
+
+    /// Add the latest version and the supported versions of `T`
+    pub fn insert_type<T: Versioned + ?Sized>(&mut self) {
+        let type_id = std::any::type_name::<T>();
+        self.latest.insert(type_id.to_owned(), T::LATEST_VERSION);
+        self.versions
+            .insert(type_id.to_owned(), T::VERSIONS.to_vec());
+    }
+}
+
+impl VersionRegistry {
+    /// A snapshot of the registered version groups
+    pub fn snapshot(&self) -> VersionSnapshot<'static> {
+        let mut snapshot = VersionSnapshot::new();
+        for uri in self.uris() {
+            if let Some(version_map) = self.get(&uri) {
+                snapshot.insert_group(uri, &*version_map);
+            }
+        }
+        snapshot
+    }
+}
+
+/// A type deriving `DeserializeVersioned`, collected statically
+///
+/// Use `VersionSnapshot::insert_static_types` to add all the collected types to a snapshot.
+#[cfg(feature = "inventory-support")]
+pub struct StaticVersionedType {
+    #[doc(hidden)]
+    pub type_id: fn() -> &'static str,
+    #[doc(hidden)]
+    pub latest_version: usize,
+    #[doc(hidden)]
+    pub versions: &'static [usize],
+}
+
+#[cfg(feature = "inventory-support")]
+inventory::collect!(StaticVersionedType);
+
+#[cfg(feature = "inventory-support")]
+impl<'a> VersionSnapshot<'a> {
+    /// Add the latest version and the supported versions of all the non generic types
+    /// deriving `DeserializeVersioned` in the crates linked to the program
+    pub fn insert_static_types(&mut self) {
+        for versioned_type in inventory::iter::<StaticVersionedType> {
+            let type_id = (versioned_type.type_id)();
+            self.latest
+                .insert(type_id.to_owned(), versioned_type.latest_version);
+            self.versions
+                .insert(type_id.to_owned(), versioned_type.versions.to_vec());
+        }
+    }
+}
@@ -51,12 +51,6 @@ impl<'a> VersionGroupURI<'a> {
     }
 }
 
-impl<'a> ToString for VersionGroupURI<'a> {
-    fn to_string(&self) -> String {
-        self.source.clone().into_owned()
-    }
-}
-
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Fail)]
 #[fail(display = "Invalid format {}, expected \"api_group:version\"", source)]
 pub struct TryFromError {
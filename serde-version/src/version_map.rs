@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
 pub use aggregate::{
-    AggregateVersionMap, AggregateVersionMapError, TypeInMultipleVersionGroups, UnknownVersionURI,
+    AggregateVersionMap, AggregateVersionMapError, AggregatedVersionMap, CachedAggregator,
+    ConflictPolicy, TypeInMultipleVersionGroups, UnknownVersionURI,
 };
+pub use sunset::{DeprecatedVersion, SunsetPolicy, UnsupportedVersion, UnsupportedVersionGroup};
 
 /// Maps the version number for each deserialization type name
 pub trait VersionMap: Clone + Sync + for<'a> VersionMapIter<'a> {
@@ -18,7 +20,9 @@ pub type DefaultVersionMap<'a> = HashMap<&'a str, usize>;
 mod aggregate {
     use crate::version_map::VersionMapIter;
     use crate::{VersionGroupResolver, VersionGroupURI, VersionMap};
+    use std::borrow::Borrow;
     use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
     #[derive(Debug, Fail)]
     #[fail(
@@ -43,68 +47,530 @@ mod aggregate {
         UnknownVersionURI(UnknownVersionURI),
         #[fail(display = "{}", _0)]
         TypeInMultipleVersionGroups(TypeInMultipleVersionGroups),
+        #[fail(display = "{}", _0)]
+        UnsupportedVersion(super::UnsupportedVersion),
+        #[fail(display = "{}", _0)]
+        UnsupportedVersionGroup(super::UnsupportedVersionGroup),
     }
 
+    /// The version map built from multiple version groups
+    pub type AggregatedVersionMap = HashMap<String, usize>;
+
     /// Implement this trait to provide a method to aggregate version maps
     ///
-    /// The default implementation will create a new instance at each call.
-    /// But you can override this behaviour to provide a cache system.
+    /// `ConflictPolicy` and `()` (with `ConflictPolicy::Error`) will create a new instance at each call.
+    /// Use `CachedAggregator` to reuse the version maps previously aggregated.
     pub trait AggregateVersionMap {
+        /// The aggregated version map, either owned or shared
+        type VM: Borrow<AggregatedVersionMap>;
+
         /// Build a new `VersionMap` that contains all the information for
         /// all provided version uris
         fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
             &self,
             uris: &[VersionGroupURI<'a>],
             resolver: &VMR,
-        ) -> Result<HashMap<String, usize>, AggregateVersionMapError>
+        ) -> Result<Self::VM, AggregateVersionMapError>
+        where
+            VMR::VM: VersionMap;
+    }
+
+    /// How to aggregate version groups that define the version of the same type
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub enum ConflictPolicy {
+        /// The type can't be defined by multiple version groups
+        #[default]
+        Error,
+        /// The type can be defined by multiple version groups with the same version
+        AllowEqual,
+        /// The version of the first version group defining the type is used
+        FirstWins,
+        /// The version of the last version group defining the type is used
+        LastWins,
+        /// The highest version defined for the type is used
+        HighestVersion,
+    }
+
+    impl ConflictPolicy {
+        /// Whether two version groups can define the versions `first` and `second` of the same type
+        pub(crate) fn allows(self, first: usize, second: usize) -> bool {
+            match self {
+                ConflictPolicy::Error => false,
+                ConflictPolicy::AllowEqual => first == second,
+                ConflictPolicy::FirstWins
+                | ConflictPolicy::LastWins
+                | ConflictPolicy::HighestVersion => true,
+            }
+        }
+
+        /// Whether the aggregated version map depends on the order of the version groups
+        pub fn is_order_dependent(self) -> bool {
+            match self {
+                ConflictPolicy::FirstWins | ConflictPolicy::LastWins => true,
+                ConflictPolicy::Error
+                | ConflictPolicy::AllowEqual
+                | ConflictPolicy::HighestVersion => false,
+            }
+        }
+    }
+
+    impl AggregateVersionMap for ConflictPolicy {
+        type VM = AggregatedVersionMap;
+
+        fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
+            &self,
+            uris: &[VersionGroupURI<'a>],
+            resolver: &VMR,
+        ) -> Result<Self::VM, AggregateVersionMapError>
         where
             VMR::VM: VersionMap,
         {
-            let mut result = HashMap::new();
-            for (i, uri) in uris.iter().enumerate() {
-                if let Some(version_map) = resolver.resolve(uri) {
-                    for (k, v) in version_map.iter() {
-                        if result.get(k).is_some() {
-                            // We found two version maps requesting a version
-                            // of the same type.
-                            // Version map must have disjoint keys.
-                            //
-                            // This should happens during development, so we take
-                            // time here to find exactly where this type is defined
-                            // to have the appropriate error.
-
-                            for j in 0..(i - 1) {
-                                // unwrap: We know previous uri have a version_map
-                                let version_map = resolver.resolve(&uris[j]).unwrap();
-                                if version_map.get(k).is_some() {
-                                    // We found the other version map
-                                    return Err(
-                                        AggregateVersionMapError::TypeInMultipleVersionGroups(
-                                            TypeInMultipleVersionGroups {
-                                                type_name: k.to_string(),
-                                                uri_1: uri.to_static(),
-                                                uri_2: uris[i].to_static(),
-                                            },
-                                        ),
-                                    );
-                                }
-                            }
-                        }
-
-                        result.insert(k.to_string(), v);
+            aggregate(uris, resolver, *self)
+        }
+    }
+
+    /// Aggregate with `ConflictPolicy::Error`
+    impl AggregateVersionMap for () {
+        type VM = AggregatedVersionMap;
+
+        fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
+            &self,
+            uris: &[VersionGroupURI<'a>],
+            resolver: &VMR,
+        ) -> Result<Self::VM, AggregateVersionMapError>
+        where
+            VMR::VM: VersionMap,
+        {
+            aggregate(uris, resolver, ConflictPolicy::Error)
+        }
+    }
+
+    fn aggregate<'a, VMR: VersionGroupResolver>(
+        uris: &[VersionGroupURI<'a>],
+        resolver: &VMR,
+        policy: ConflictPolicy,
+    ) -> Result<AggregatedVersionMap, AggregateVersionMapError>
+    where
+        VMR::VM: VersionMap,
+    {
+        // The version of each type and the index of the uri defining it
+        let mut result: HashMap<String, (usize, usize)> = HashMap::new();
+        for (i, uri) in uris.iter().enumerate() {
+            let version_map = resolver.resolve(uri).ok_or_else(|| {
+                AggregateVersionMapError::UnknownVersionURI(UnknownVersionURI {
+                    uri: uri.to_static(),
+                })
+            })?;
+
+            for (k, v) in version_map.iter() {
+                if let Some((version, index)) = result.get_mut(k) {
+                    if !policy.allows(*version, v) {
+                        return Err(AggregateVersionMapError::TypeInMultipleVersionGroups(
+                            TypeInMultipleVersionGroups {
+                                type_name: k.to_string(),
+                                uri_1: uris[*index].to_static(),
+                                uri_2: uri.to_static(),
+                            },
+                        ));
+                    }
+                    let replace = match policy {
+                        ConflictPolicy::Error
+                        | ConflictPolicy::AllowEqual
+                        | ConflictPolicy::FirstWins => false,
+                        ConflictPolicy::LastWins => true,
+                        ConflictPolicy::HighestVersion => v > *version,
+                    };
+                    if replace {
+                        *version = v;
+                        *index = i;
                     }
                 } else {
-                    return Err(AggregateVersionMapError::UnknownVersionURI(
-                        UnknownVersionURI {
-                            uri: uri.to_static(),
-                        },
-                    ));
+                    result.insert(k.to_string(), (v, i));
                 }
             }
-            Ok(result)
+        }
+        Ok(result
+            .into_iter()
+            .map(|(k, (version, _))| (k, version))
+            .collect())
+    }
+
+    /// An aggregator that keeps the version maps previously aggregated
+    ///
+    /// The version maps are identified by their version uris, regardless of their order
+    /// unless the `ConflictPolicy` depends on it.
+    /// So a `CachedAggregator` must always be used with the same resolver.
+    ///
+    /// It is thread-safe and keeps at most `capacity` version maps,
+    /// the least recently used is dropped first.
+    pub struct CachedAggregator {
+        capacity: usize,
+        policy: ConflictPolicy,
+        cache: Mutex<Cache>,
+    }
+
+    struct Cache {
+        tick: u64,
+        entries: HashMap<Vec<String>, CacheEntry>,
+    }
+
+    struct CacheEntry {
+        version_map: Arc<AggregatedVersionMap>,
+        last_used: u64,
+    }
+
+    impl CachedAggregator {
+        /// Create an aggregator that keeps at most `capacity` version maps
+        ///
+        /// Version groups are aggregated with `ConflictPolicy::Error`.
+        pub fn new(capacity: usize) -> Self {
+            Self::with_policy(capacity, ConflictPolicy::Error)
+        }
+
+        /// Create an aggregator that keeps at most `capacity` version maps
+        /// aggregated with `policy`
+        pub fn with_policy(capacity: usize, policy: ConflictPolicy) -> Self {
+            Self {
+                capacity,
+                policy,
+                cache: Mutex::new(Cache {
+                    tick: 0,
+                    entries: HashMap::new(),
+                }),
+            }
+        }
+
+        /// Maximum number of version maps kept
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        /// Policy used to aggregate the version groups
+        pub fn policy(&self) -> ConflictPolicy {
+            self.policy
+        }
+
+        /// Number of version maps currently kept
+        pub fn len(&self) -> usize {
+            self.lock().entries.len()
+        }
+
+        /// Whether no version maps are currently kept
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Drop all the version maps kept
+        pub fn clear(&self) {
+            self.lock().entries.clear();
+        }
+
+        fn lock(&self) -> MutexGuard<'_, Cache> {
+            // The cache is always in a valid state, even if a thread panicked
+            self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl Default for CachedAggregator {
+        fn default() -> Self {
+            Self::new(64)
+        }
+    }
+
+    impl AggregateVersionMap for CachedAggregator {
+        type VM = Arc<AggregatedVersionMap>;
+
+        fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
+            &self,
+            uris: &[VersionGroupURI<'a>],
+            resolver: &VMR,
+        ) -> Result<Self::VM, AggregateVersionMapError>
+        where
+            VMR::VM: VersionMap,
+        {
+            let mut key = uris.iter().map(ToString::to_string).collect::<Vec<_>>();
+            if !self.policy.is_order_dependent() {
+                key.sort();
+            }
+
+            {
+                let mut cache = self.lock();
+                cache.tick += 1;
+                let tick = cache.tick;
+                if let Some(entry) = cache.entries.get_mut(&key) {
+                    entry.last_used = tick;
+                    return Ok(entry.version_map.clone());
+                }
+            }
+
+            // Aggregate without holding the lock, errors are not kept
+            let version_map = Arc::new(aggregate(uris, resolver, self.policy)?);
+
+            if self.capacity > 0 {
+                let mut cache = self.lock();
+                if cache.entries.len() >= self.capacity && !cache.entries.contains_key(&key) {
+                    let least_recently_used = cache
+                        .entries
+                        .iter()
+                        .min_by_key(|(_, entry)| entry.last_used)
+                        .map(|(key, _)| key.clone());
+                    if let Some(least_recently_used) = least_recently_used {
+                        cache.entries.remove(&least_recently_used);
+                    }
+                }
+                cache.tick += 1;
+                let last_used = cache.tick;
+                cache.entries.insert(
+                    key,
+                    CacheEntry {
+                        version_map: version_map.clone(),
+                        last_used,
+                    },
+                );
+            }
+
+            Ok(version_map)
+        }
+    }
+}
+
+mod sunset {
+    use crate::{
+        AggregateVersionMap, AggregateVersionMapError, VersionGroupResolver, VersionGroupURI,
+        VersionMap,
+    };
+    use std::borrow::Borrow;
+    use std::cmp::Ordering;
+    use std::collections::BTreeMap;
+    use std::fmt::Display;
+
+    /// Error used when the version of a type is no longer supported
+    #[derive(Debug, Clone, PartialEq, Eq, Fail)]
+    #[fail(
+        display = "Version {} of type {} is no longer supported, the minimum supported version is {}",
+        version, type_id, min_supported
+    )]
+    pub struct UnsupportedVersion {
+        pub type_id: String,
+        pub version: usize,
+        pub min_supported: usize,
+    }
+
+    /// Error used when the version uri of an api group is no longer supported
+    #[derive(Debug, Clone, PartialEq, Eq, Fail)]
+    #[fail(
+        display = "Version uri {} is no longer supported, the minimum supported version of its api group is {}",
+        uri, min_supported
+    )]
+    pub struct UnsupportedVersionGroup {
+        pub uri: String,
+        pub min_supported: String,
+    }
+
+    /// A deprecated version of a type, it is still supported
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DeprecatedVersion {
+        pub type_id: String,
+        pub version: usize,
+        pub min_recommended: usize,
+    }
+
+    impl Display for DeprecatedVersion {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+            write!(
+                f,
+                "Version {} of type {} is deprecated, upgrade to version {} or later",
+                self.version, self.type_id, self.min_recommended
+            )
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, Default)]
+    struct Sunset {
+        min_supported: usize,
+        min_recommended: usize,
+    }
+
+    /// An aggregator that enforces the deprecation cycle of the versions of the types
+    ///
+    /// For each type, the versions below the minimum supported version are rejected with
+    /// `AggregateVersionMapError::UnsupportedVersion`, and the versions below the minimum
+    /// recommended version are deprecated: they are still loaded, but reported to the
+    /// `on_deprecated` handler. By default, deprecated versions are logged as warnings
+    /// with the `log-support` feature, and ignored otherwise.
+    ///
+    /// The sunsets are set per type, not per version uri: a version group only selects
+    /// the versions of its types, and the same version of a type is usually reachable
+    /// from several version groups. Retiring a type version retires it whatever
+    /// the version uris of the document.
+    ///
+    /// To retire whole version groups, set the minimum supported version of an api group
+    /// with `unsupported_below_group`, the version uris below it are rejected with
+    /// `AggregateVersionMapError::UnsupportedVersionGroup` before aggregating them.
+    ///
+    /// The version groups are aggregated by the wrapped aggregator.
+    pub struct SunsetPolicy<AGG = ()> {
+        aggregate: AGG,
+        sunsets: BTreeMap<&'static str, Sunset>,
+        group_sunsets: BTreeMap<String, String>,
+        on_deprecated: Box<dyn Fn(&DeprecatedVersion) + Send + Sync>,
+    }
+
+    impl<AGG> SunsetPolicy<AGG> {
+        /// Create a policy without sunset, using `aggregate` to aggregate the version groups
+        pub fn new(aggregate: AGG) -> Self {
+            Self {
+                aggregate,
+                sunsets: BTreeMap::new(),
+                group_sunsets: BTreeMap::new(),
+                on_deprecated: Box::new(report_deprecated),
+            }
+        }
+
+        /// Reject the versions of `T` below `min_supported`
+        pub fn unsupported_below<T: ?Sized>(mut self, min_supported: usize) -> Self {
+            self.sunset::<T>().min_supported = min_supported;
+            self
+        }
+
+        /// Deprecate the versions of `T` below `min_recommended`
+        pub fn deprecated_below<T: ?Sized>(mut self, min_recommended: usize) -> Self {
+            self.sunset::<T>().min_recommended = min_recommended;
+            self
+        }
+
+        /// Reject the version uris of `api_group` with a version below `min_supported`
+        ///
+        /// The versions are compared by their dot separated components,
+        /// numerically when both components are numbers: `1.2` is below `1.10`.
+        pub fn unsupported_below_group<G, V>(mut self, api_group: G, min_supported: V) -> Self
+        where
+            G: Into<String>,
+            V: Into<String>,
+        {
+            self.group_sunsets
+                .insert(api_group.into(), min_supported.into());
+            self
+        }
+
+        /// Report the deprecated versions to `on_deprecated`
+        pub fn on_deprecated<F>(mut self, on_deprecated: F) -> Self
+        where
+            F: Fn(&DeprecatedVersion) + Send + Sync + 'static,
+        {
+            self.on_deprecated = Box::new(on_deprecated);
+            self
+        }
+
+        /// The minimum supported version of a type, if any
+        pub fn min_supported(&self, type_id: &str) -> Option<usize> {
+            self.sunsets
+                .get(type_id)
+                .map(|sunset| sunset.min_supported)
+                .filter(|min_supported| *min_supported > 0)
+        }
+
+        /// Check the version uris against the minimum supported versions of their api group
+        pub fn check_uris(&self, uris: &[VersionGroupURI]) -> Result<(), UnsupportedVersionGroup> {
+            for uri in uris {
+                if let Some(min_supported) = self.group_sunsets.get(uri.api_group()) {
+                    if compare_versions(uri.version(), min_supported) == Ordering::Less {
+                        return Err(UnsupportedVersionGroup {
+                            uri: uri.to_string(),
+                            min_supported: min_supported.clone(),
+                        });
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Check the versions of a version map
+        ///
+        /// The types are checked in the order of their names, the first unsupported one
+        /// is returned and the deprecated versions are reported to the `on_deprecated` handler.
+        pub fn check<VM: VersionMap>(&self, version_map: &VM) -> Result<(), UnsupportedVersion> {
+            for (type_id, sunset) in &self.sunsets {
+                let version = match version_map.get(type_id) {
+                    Some(version) => version,
+                    None => continue,
+                };
+                if version < sunset.min_supported {
+                    return Err(UnsupportedVersion {
+                        type_id: (*type_id).to_owned(),
+                        version,
+                        min_supported: sunset.min_supported,
+                    });
+                }
+                if version < sunset.min_recommended {
+                    (self.on_deprecated)(&DeprecatedVersion {
+                        type_id: (*type_id).to_owned(),
+                        version,
+                        min_recommended: sunset.min_recommended,
+                    });
+                }
+            }
+            Ok(())
+        }
+
+        fn sunset<T: ?Sized>(&mut self) -> &mut Sunset {
+            self.sunsets.entry(std::any::type_name::<T>()).or_default()
+        }
+    }
+
+    /// Compare two versions by their dot separated components
+    fn compare_versions(left: &str, right: &str) -> Ordering {
+        let mut left = left.split('.');
+        let mut right = right.split('.');
+        loop {
+            let ordering = match (left.next(), right.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(left), Some(right)) => match (left.parse::<u64>(), right.parse::<u64>()) {
+                    (Ok(left), Ok(right)) => left.cmp(&right),
+                    _ => left.cmp(right),
+                },
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+
+    #[cfg(feature = "log-support")]
+    fn report_deprecated(deprecated: &DeprecatedVersion) {
+        ::log::warn!("{}", deprecated);
+    }
+
+    #[cfg(not(feature = "log-support"))]
+    fn report_deprecated(_: &DeprecatedVersion) {}
+
+    impl<AGG: Default> Default for SunsetPolicy<AGG> {
+        fn default() -> Self {
+            Self::new(AGG::default())
+        }
+    }
+
+    impl<AGG: AggregateVersionMap> AggregateVersionMap for SunsetPolicy<AGG> {
+        type VM = AGG::VM;
+
+        fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
+            &self,
+            uris: &[VersionGroupURI<'a>],
+            resolver: &VMR,
+        ) -> Result<Self::VM, AggregateVersionMapError>
+        where
+            VMR::VM: VersionMap,
+        {
+            self.check_uris(uris)
+                .map_err(AggregateVersionMapError::UnsupportedVersionGroup)?;
+            let version_map = self.aggregate.aggregate_version_maps(uris, resolver)?;
+            self.check(version_map.borrow())
+                .map_err(AggregateVersionMapError::UnsupportedVersion)?;
+            Ok(version_map)
         }
     }
-    impl AggregateVersionMap for () {}
 }
 
 mod version_map_impls {
@@ -6,6 +6,10 @@ use serde::de::{DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess
 use serde::{Deserialize, Deserializer};
 
 /// Wrap a visitor to wrap seed or call specialized methods
+///
+/// It also wraps `SeqAccess`, `MapAccess`, `EnumAccess` and `VariantAccess` so the
+/// version map is propagated to the nested values.
+/// See `wrap_seq_access` and `wrap_map_access` to use it in hand-written `Deserialize` impls.
 pub struct VersionedVisitor<'v, V, VM> {
     visitor: V,
     version_map: VM,
@@ -13,6 +17,7 @@ pub struct VersionedVisitor<'v, V, VM> {
 }
 
 impl<'v, V, VM> VersionedVisitor<'v, V, VM> {
+    /// Wrap `visitor` and propagate `version_map` to the nested values
     pub fn new(visitor: V, version_map: VM) -> Self {
         Self {
             visitor,
@@ -23,15 +28,16 @@ impl<'v, V, VM> VersionedVisitor<'v, V, VM> {
 }
 
 macro_rules! forward_visit {
-        ($name:ident, $ty:ty) => {
-            #[inline]
-            fn $name<E>(self, v: $ty) -> Result<V::Value, E>
-                where E: serde::de::Error
-            {
-                self.visitor.$name(v)
-            }
+    ($name:ident, $ty:ty) => {
+        #[inline]
+        fn $name<E>(self, v: $ty) -> Result<V::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.$name(v)
         }
-    }
+    };
+}
 
 impl<'de, V, VM> Visitor<'de> for VersionedVisitor<'de, V, VM>
 where
@@ -93,8 +99,27 @@ where
     where
         D: Deserializer<'de>,
     {
+        if !<V::Value as VisitNewtypeAsSeq>::visit_newtype_as_seq() {
+            return self
+                .visitor
+                .visit_newtype_struct(VersionedDeserializer::new(deserializer, self.version_map))
+                .map_err(|err| err.into_error());
+        }
+
+        // Derived `Deserialize` implementations call directly `Deserialize::deserialize`
+        // on the content of a newtype struct, so its versioning would be ignored.
+        //
+        // Instead, the content is provided as a sequence with a single element,
+        // `SeqAccess::next_element` dispatches to `DeserializeVersioned::next_element`.
+        let seq_access = VersionedVisitor {
+            visitor: NewtypeSeqAccess {
+                deserializer: Some(deserializer),
+            },
+            version_map: self.version_map,
+            marker: std::marker::PhantomData,
+        };
         self.visitor
-            .visit_newtype_struct(VersionedDeserializer::new(deserializer, self.version_map))
+            .visit_seq(seq_access)
             .map_err(|err| err.into_error())
     }
 
@@ -144,6 +169,53 @@ where
     }
 }
 
+/// Whether the visitor of a newtype struct accepts its content as a sequence
+///
+/// The content of a newtype struct is provided to `visit_newtype_struct`, and deserialized
+/// without versioning support by the visitors generated by `#[derive(Deserialize)]`.
+/// When this returns `true` for the value of the visitor, the content is provided
+/// to `visit_seq` instead, as a sequence with a single element deserialized with versioning support.
+///
+/// It returns `false` for all types, `#[derive(DeserializeVersioned)]` specializes it
+/// for newtype structs as the visitors generated by `#[derive(Deserialize)]` implement `visit_seq`.
+pub trait VisitNewtypeAsSeq {
+    /// Whether the content of the newtype struct is provided to `visit_seq`
+    fn visit_newtype_as_seq() -> bool;
+}
+
+impl<T: ?Sized> VisitNewtypeAsSeq for T {
+    default fn visit_newtype_as_seq() -> bool {
+        false
+    }
+}
+
+/// A sequence with a single element: the content of a newtype struct
+struct NewtypeSeqAccess<D> {
+    deserializer: Option<D>,
+}
+
+impl<'de, D> SeqAccess<'de> for NewtypeSeqAccess<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    #[inline]
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.deserializer.take() {
+            Some(deserializer) => seed.deserialize(deserializer).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(if self.deserializer.is_some() { 1 } else { 0 })
+    }
+}
+
 impl<'de, V, VM> SeqAccess<'de> for VersionedVisitor<'de, V, VM>
 where
     V: SeqAccess<'de>,
@@ -246,6 +318,21 @@ where
             .map_err(|err| err.reduce())
     }
 
+    #[inline]
+    fn next_entry<K, V2>(&mut self) -> Result<Option<(K, V2)>, Self::Error>
+    where
+        K: Deserialize<'de>,
+        V2: Deserialize<'de>,
+    {
+        match self.next_key()? {
+            Some(key) => {
+                let value = self.next_value()?;
+                Ok(Some((key, value)))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn size_hint(&self) -> Option<usize> {
         self.visitor.size_hint()
     }
@@ -0,0 +1,227 @@
+//! Serialization utilities for the YAML format
+//!
+//! The version header is either a top-level `v` key of the document:
+//! ```yaml
+//! v: ["org.my.company:1.0.2"]
+//! name: my config name
+//! ```
+//!
+//! or a front-matter document preceding the payload document:
+//! ```yaml
+//! v: ["org.my.company:1.0.2"]
+//! ---
+//! name: my config name
+//! ```
+
+use crate::value::VersionedValue;
+use crate::version_map::AggregateVersionMap;
+use crate::{
+    AggregateVersionMapError, AggregatedVersionMap, DeserializeVersioned, Error, UncoveredType,
+    VersionGroupResolver, VersionGroupURIs, VersionMap, VersionRegistry, VersionedDeserializer,
+};
+use failure::Fail;
+use serde::de::Deserializer;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::io::Read;
+
+/// Deserialization error
+#[derive(Debug, Fail)]
+pub enum DeserializeError {
+    #[fail(display = "{}", _0)]
+    Yaml(::serde_yaml::Error),
+    #[fail(display = "{}", _0)]
+    De(Error<Error<::serde_yaml::Error>>),
+    #[fail(display = "{}", _0)]
+    AggregateError(AggregateVersionMapError),
+    #[fail(display = "Missing version header 'v'")]
+    MissingVersionHeader,
+    #[fail(
+        display = "Expected a document or a front-matter and a document, found {} documents",
+        count
+    )]
+    InvalidDocumentCount { count: usize },
+    #[fail(display = "{}", _0)]
+    Io(std::io::Error),
+}
+impl_from_enum! {
+    DeserializeError::Yaml => ::serde_yaml::Error,
+    DeserializeError::De => Error<Error<::serde_yaml::Error>>,
+    DeserializeError::AggregateError => AggregateVersionMapError,
+    DeserializeError::Io => std::io::Error,
+}
+
+/// Serialization error
+#[derive(Debug, Fail)]
+pub enum SerializeError {
+    #[fail(display = "{}", _0)]
+    Yaml(::serde_yaml::Error),
+    #[fail(display = "The value is not a mapping, use a front-matter version header")]
+    NotAMapping,
+    #[fail(display = "{}", _0)]
+    UncoveredType(UncoveredType),
+}
+impl_from_enum! {
+    SerializeError::Yaml => ::serde_yaml::Error,
+    SerializeError::UncoveredType => UncoveredType,
+}
+
+fn deserialize_document<'de, T, D, VMR, AGG>(
+    uris: &VersionGroupURIs,
+    document: D,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    D: Deserializer<'de, Error = ::serde_yaml::Error>,
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let version_map = aggregate.aggregate_version_maps(uris, resolver)?;
+    let version_map: &AggregatedVersionMap = version_map.borrow();
+
+    let de = VersionedDeserializer::new(document, version_map);
+    Ok(crate::scope(version_map, || {
+        DeserializeVersioned::deserialize_versioned(de, version_map)
+    })?)
+}
+
+/// Deserialize a YAML string with versioning support
+///
+/// The version header is a top-level `v` key of the document,
+/// or a front-matter document preceding the payload document.
+///
+/// # Generic Parameters
+/// - `T`: type to deserialize
+/// - `VMR`: resolver to find the version groups to use
+/// - `AGG`: aggregator to combine found version groups
+///
+/// # Parameters
+/// - `input`: YAML formatted string
+/// - `resolver`: resolver to find the version groups to use
+/// - `aggregate`: aggregator to combine found version groups
+///
+/// # Returns
+/// The deserialized value or the error that occurred
+pub fn deserialize<'de, T, VMR, AGG>(
+    input: &'de str,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    VMR::VM: VersionMap,
+    T: DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut documents = ::serde_yaml::Deserializer::from_str(input).collect::<Vec<_>>();
+    match documents.len() {
+        1 => {
+            let mut value: ::serde_yaml::Value =
+                serde::Deserialize::deserialize(documents.remove(0))?;
+            let header = value
+                .take_version_header()
+                .ok_or(DeserializeError::MissingVersionHeader)?;
+            let uris: VersionGroupURIs =
+                serde::Deserialize::deserialize(header).map(|uris: Vec<_>| uris.into())?;
+            deserialize_document(&uris, value, resolver, aggregate)
+        }
+        2 => {
+            let document = documents.remove(1);
+            let uris: VersionGroupURIs = serde::Deserialize::deserialize(documents.remove(0))?;
+            deserialize_document(&uris, document, resolver, aggregate)
+        }
+        count => Err(DeserializeError::InvalidDocumentCount { count }),
+    }
+}
+
+/// Deserialize a YAML document with versioning support from a reader
+///
+/// The version header can be a top-level key of the document, so the reader is read
+/// to the end before the document is deserialized.
+///
+/// See `deserialize`.
+pub fn deserialize_from_reader<R, T, VMR, AGG>(
+    mut reader: R,
+    resolver: &VMR,
+    aggregate: &AGG,
+) -> Result<T, DeserializeError>
+where
+    R: Read,
+    VMR::VM: VersionMap,
+    T: for<'de> DeserializeVersioned<'de, VMR::VM>,
+    VMR: VersionGroupResolver,
+    AGG: AggregateVersionMap,
+{
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    deserialize(&input, resolver, aggregate)
+}
+
+/// Serialize a value formatted in YAML with its version header as a top-level `v` key
+///
+/// # Generic Parameters
+/// - `T`: type to serialize, it must be serialized as a mapping
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+///
+/// # Returns
+/// The formatted string or the error that occurred, if any.
+pub fn serialize<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<String, SerializeError> {
+    let fields = match ::serde_yaml::to_value(value)? {
+        ::serde_yaml::Value::Mapping(fields) => fields,
+        _ => return Err(SerializeError::NotAMapping),
+    };
+
+    let mut mapping = ::serde_yaml::Mapping::new();
+    mapping.insert("v".into(), ::serde_yaml::to_value(&**uris)?);
+    mapping.extend(fields);
+    Ok(::serde_yaml::to_string(&mapping)?)
+}
+
+/// Serialize a value formatted in YAML with the latest version header as a top-level `v` key
+///
+/// The version header contains the registered version uris that define the latest version
+/// of all versioned types reachable from `T`, see `VersionRegistry::latest_uris`.
+///
+/// # Generic Parameters
+/// - `T`: type to serialize, it must be serialized as a mapping
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `registry`: The current version groups
+///
+/// # Returns
+/// The formatted string or the error that occurred, if any.
+pub fn serialize_latest<T: Serialize>(
+    value: &T,
+    registry: &VersionRegistry,
+) -> Result<String, SerializeError> {
+    let uris = registry.latest_uris::<T>()?;
+    serialize(value, &uris)
+}
+
+/// Serialize a value formatted in YAML with its version header in a front-matter document
+///
+/// # Parameters
+/// - `value`: The value to serialize
+/// - `uris`: The uris to serialize in the version header
+///
+/// # Returns
+/// The formatted string or the error that occurred, if any.
+pub fn serialize_front_matter<T: Serialize>(
+    value: &T,
+    uris: &VersionGroupURIs,
+) -> Result<String, ::serde_yaml::Error> {
+    let mut output = ::serde_yaml::to_string(uris)?;
+    output.push_str("---\n");
+    output.push_str(&::serde_yaml::to_string(value)?);
+    Ok(output)
+}
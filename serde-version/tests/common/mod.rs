@@ -0,0 +1,140 @@
+// The token deserializer is provided by the `serde-version-test` crate
+
+#[macro_export]
+macro_rules! declare_tests_versions {
+    (
+        fail $name:ident ($($vm:tt)*) { $($ty:ty: $value:expr =>$tokens:expr,)+ }
+        $($tt:tt)*
+    ) => {
+            #[test]
+            fn $name() {
+                let version_map = build_version_map!($($vm)*);
+                let version_map = get_version_map!(version_map, $($vm)*);
+
+                $(
+                    let mut de = ::serde_version_test::Deserializer::new($tokens);
+                    let de_versioned = VersionedDeserializer::new(&mut de, version_map);
+                    match <$ty as DeserializeVersioned<'_, _>>::deserialize_versioned(de_versioned, version_map) {
+                        Ok(_) => {
+                            panic!("tokens should have failed to deserialize")
+                        }
+                        Err(e) => assert_eq!(format!("{}", $value), format!("{}", e)),
+                    };
+                )+
+            }
+
+            declare_tests_versions! { $($tt)* }
+    };
+    (
+        scoped $name:ident ($($vm:tt)*) { $($ty:ty: $value:expr => $tokens:expr,)+ }
+        $($tt:tt)*
+    ) => {
+            #[test]
+            fn $name() {
+                #[allow(unused_variables)]
+                let version_map = build_version_map!($($vm)*);
+                let version_map = get_version_map!(version_map, $($vm)*);
+
+                $(
+                    // Deserialize within a scope for the content buffered by serde
+                    let mut de = ::serde_version_test::Deserializer::new($tokens);
+                    let value = ::serde_version::scope(version_map, || {
+                        let de_versioned = ::serde_version::VersionedDeserializer::new(&mut de, version_map);
+                        <$ty as ::serde_version::DeserializeVersioned<'_, _>>::deserialize_versioned(de_versioned, version_map)
+                    });
+                    match value {
+                        Ok(v) => assert_eq!($value, v),
+                        Err(e) => panic!("tokens failed to deserialize: {}", e),
+                    };
+                    if de.remaining() > 0 {
+                        panic!("{} remaining tokens", de.remaining());
+                    }
+                )+
+            }
+
+            declare_tests_versions! { $($tt)* }
+    };
+    (
+        $name:ident ($($vm:tt)*) { $($ty:ty: $value:expr => $tokens:expr,)+ }
+        $($tt:tt)*
+    ) => {
+            #[test]
+            fn $name() {
+                #[allow(unused_variables)]
+                let version_map = build_version_map!($($vm)*);
+                let version_map = get_version_map!(version_map, $($vm)*);
+
+                $(
+                    // Test ser/de roundtripping
+                    let mut de = ::serde_version_test::Deserializer::new($tokens);
+                    let de_versioned = ::serde_version::VersionedDeserializer::new(&mut de, version_map);
+                    match <$ty as ::serde_version::DeserializeVersioned<'_, _>>::deserialize_versioned(de_versioned, version_map) {
+                        Ok(v) => {
+                            assert_eq!($value, v);
+                            v
+                        }
+                        Err(e) => panic!("tokens failed to deserialize: {}", e),
+                    };
+                    if de.remaining() > 0 {
+                        panic!("{} remaining tokens", de.remaining());
+                    }
+                )+
+            }
+
+            declare_tests_versions! { $($tt)* }
+    };
+    () => { }
+}
+
+// Used in macro
+#[allow(unused_macros)]
+macro_rules! build_version_map {
+    // Build version map
+    ($($version_ty:expr => $version_num:expr),*) => {
+         vec![$(($version_ty, $version_num),)*]
+                    .into_iter().collect::<DefaultVersionMap>()
+    };
+    // From static
+    ($name:expr) => { () };
+}
+
+// Used in macro
+#[allow(unused_macros)]
+macro_rules! get_version_map {
+    // Build version map
+    ($id:ident, $($version_ty:expr => $version_num:expr),*) => {
+        &$id
+    };
+    // From static
+    ($id:ident, $name:expr) => {
+        $name
+    };
+}
+// The versioned type shared by the format tests:
+// the field `a` of the version 1 was renamed `b` in the version 2
+#[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "A")]
+pub struct Av1 {
+    pub a: u8,
+}
+
+#[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize, DeserializeVersioned, PartialEq, Debug)]
+#[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+pub struct A {
+    pub b: u8,
+}
+
+impl From<Av1> for A {
+    fn from(v: Av1) -> Self {
+        Self { b: v.a }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+pub struct Container {
+    pub a: A,
+    pub others: Vec<A>,
+}
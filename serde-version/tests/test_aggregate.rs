@@ -0,0 +1,323 @@
+#[macro_use]
+extern crate serde_version;
+
+use serde_version::{
+    AggregateVersionMap, AggregateVersionMapError, CachedAggregator, ConflictPolicy,
+    DeprecatedVersion, SunsetPolicy, UnsupportedVersion, UnsupportedVersionGroup, VersionGroupURI,
+};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+struct A;
+struct B;
+
+fn uris(uris: &[&'static str]) -> Vec<VersionGroupURI<'static>> {
+    uris.iter()
+        .map(|uri| VersionGroupURI::try_from(*uri).unwrap())
+        .collect()
+}
+
+#[test]
+fn default_aggregator_works() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("b", "1") => { B => 2, },
+    };
+
+    let version_map = ().aggregate_version_maps(&uris(&["a:1", "b:1"]), &resolver).unwrap();
+    assert_eq!(
+        vec![
+            (std::any::type_name::<A>().to_owned(), 1),
+            (std::any::type_name::<B>().to_owned(), 2),
+        ]
+        .into_iter()
+        .collect::<serde_version::AggregatedVersionMap>(),
+        version_map
+    );
+}
+
+#[test]
+fn cached_aggregator_reuses_version_maps() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("b", "1") => { B => 2, },
+    };
+    let aggregator = CachedAggregator::new(4);
+
+    let first = aggregator
+        .aggregate_version_maps(&uris(&["a:1", "b:1"]), &resolver)
+        .unwrap();
+    let second = aggregator
+        .aggregate_version_maps(&uris(&["b:1", "a:1"]), &resolver)
+        .unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(1, aggregator.len());
+
+    let other = aggregator
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .unwrap();
+    assert!(!Arc::ptr_eq(&first, &other));
+    assert_eq!(2, aggregator.len());
+
+    aggregator.clear();
+    assert!(aggregator.is_empty());
+}
+
+#[test]
+fn cached_aggregator_drops_least_recently_used() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("b", "1") => { B => 2, },
+    };
+    let aggregator = CachedAggregator::new(2);
+
+    let a = aggregator
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .unwrap();
+    let b = aggregator
+        .aggregate_version_maps(&uris(&["b:1"]), &resolver)
+        .unwrap();
+    // Use `a` so `b` is the least recently used
+    aggregator
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .unwrap();
+    aggregator
+        .aggregate_version_maps(&uris(&["a:1", "b:1"]), &resolver)
+        .unwrap();
+    assert_eq!(2, aggregator.len());
+
+    let a2 = aggregator
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .unwrap();
+    let b2 = aggregator
+        .aggregate_version_maps(&uris(&["b:1"]), &resolver)
+        .unwrap();
+    assert!(Arc::ptr_eq(&a, &a2));
+    assert!(!Arc::ptr_eq(&b, &b2));
+}
+
+#[test]
+fn cached_aggregator_does_not_keep_errors() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+    };
+    let aggregator = CachedAggregator::default();
+
+    assert!(aggregator
+        .aggregate_version_maps(&uris(&["a:2"]), &resolver)
+        .is_err());
+    assert!(aggregator.is_empty());
+}
+
+#[test]
+fn overlapping_version_groups_fail() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("b", "1") => { A => 1, B => 2, },
+        ("c", "1") => { B => 2, },
+    };
+
+    let error = ().aggregate_version_maps(&uris(&["a:1", "b:1"]), &resolver).unwrap_err();
+    assert_eq!(
+        format!(
+            "Both version uri a:1 and b:1 contains the type {}",
+            std::any::type_name::<A>()
+        ),
+        format!("{}", error)
+    );
+
+    let error = ConflictPolicy::Error
+        .aggregate_version_maps(&uris(&["a:1", "c:1", "b:1"]), &resolver)
+        .unwrap_err();
+    assert!(format!("{}", error).starts_with("Both version uri "));
+}
+
+#[test]
+fn conflict_policies_work() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("a", "2") => { A => 2, },
+        ("b", "1") => { A => 1, B => 2, },
+    };
+    let version_of_a = |policy: ConflictPolicy, uris_: &[&'static str]| {
+        policy
+            .aggregate_version_maps(&uris(uris_), &resolver)
+            .map(|version_map| version_map[std::any::type_name::<A>()])
+            .ok()
+    };
+
+    assert_eq!(None, version_of_a(ConflictPolicy::Error, &["a:1", "b:1"]));
+    assert_eq!(
+        Some(1),
+        version_of_a(ConflictPolicy::AllowEqual, &["a:1", "b:1"])
+    );
+    assert_eq!(
+        None,
+        version_of_a(ConflictPolicy::AllowEqual, &["a:2", "b:1"])
+    );
+    assert_eq!(
+        Some(2),
+        version_of_a(ConflictPolicy::FirstWins, &["a:2", "b:1"])
+    );
+    assert_eq!(
+        Some(1),
+        version_of_a(ConflictPolicy::FirstWins, &["b:1", "a:2"])
+    );
+    assert_eq!(
+        Some(1),
+        version_of_a(ConflictPolicy::LastWins, &["a:2", "b:1"])
+    );
+    assert_eq!(
+        Some(2),
+        version_of_a(ConflictPolicy::LastWins, &["b:1", "a:2"])
+    );
+    assert_eq!(
+        Some(2),
+        version_of_a(ConflictPolicy::HighestVersion, &["a:2", "b:1"])
+    );
+    assert_eq!(
+        Some(2),
+        version_of_a(ConflictPolicy::HighestVersion, &["b:1", "a:2"])
+    );
+}
+
+#[test]
+fn cached_aggregator_keeps_order_for_order_dependent_policies() {
+    let resolver = version_group_resolver_new! {
+        ("a", "2") => { A => 2, },
+        ("b", "1") => { A => 1, B => 2, },
+    };
+    let aggregator = CachedAggregator::with_policy(4, ConflictPolicy::LastWins);
+
+    let first = aggregator
+        .aggregate_version_maps(&uris(&["a:2", "b:1"]), &resolver)
+        .unwrap();
+    let second = aggregator
+        .aggregate_version_maps(&uris(&["b:1", "a:2"]), &resolver)
+        .unwrap();
+    assert_eq!(1, first[std::any::type_name::<A>()]);
+    assert_eq!(2, second[std::any::type_name::<A>()]);
+    assert_eq!(2, aggregator.len());
+}
+
+#[test]
+fn sunset_policy_rejects_unsupported_versions() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, B => 1, },
+        ("a", "2") => { A => 2, B => 1, },
+    };
+    let policy = SunsetPolicy::new(()).unsupported_below::<A>(2);
+
+    match policy.aggregate_version_maps(&uris(&["a:1"]), &resolver) {
+        Err(AggregateVersionMapError::UnsupportedVersion(err)) => assert_eq!(
+            UnsupportedVersion {
+                type_id: std::any::type_name::<A>().to_owned(),
+                version: 1,
+                min_supported: 2,
+            },
+            err
+        ),
+        _ => panic!("Expected UnsupportedVersion"),
+    }
+    assert!(policy
+        .aggregate_version_maps(&uris(&["a:2"]), &resolver)
+        .is_ok());
+    assert_eq!(Some(2), policy.min_supported(std::any::type_name::<A>()));
+    assert_eq!(None, policy.min_supported(std::any::type_name::<B>()));
+}
+
+#[test]
+fn sunset_policy_checks_the_types_in_order() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, B => 1, },
+    };
+    let deprecated = Arc::new(Mutex::new(Vec::new()));
+    let policy = SunsetPolicy::new(())
+        .deprecated_below::<B>(2)
+        .deprecated_below::<A>(2)
+        .on_deprecated({
+            let deprecated = deprecated.clone();
+            move |version: &DeprecatedVersion| {
+                deprecated.lock().unwrap().push(version.type_id.clone())
+            }
+        });
+    policy
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .unwrap();
+    assert_eq!(
+        vec![
+            std::any::type_name::<A>().to_owned(),
+            std::any::type_name::<B>().to_owned(),
+        ],
+        *deprecated.lock().unwrap()
+    );
+
+    let policy = SunsetPolicy::new(())
+        .unsupported_below::<B>(2)
+        .unsupported_below::<A>(2);
+    match policy.aggregate_version_maps(&uris(&["a:1"]), &resolver) {
+        Err(AggregateVersionMapError::UnsupportedVersion(err)) => {
+            assert_eq!(std::any::type_name::<A>(), err.type_id)
+        }
+        _ => panic!("Expected UnsupportedVersion"),
+    }
+}
+
+#[test]
+fn sunset_policy_rejects_unsupported_version_groups() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1.2") => { A => 1, },
+        ("a", "1.10") => { A => 2, },
+        ("b", "1") => { B => 1, },
+    };
+    let policy = SunsetPolicy::new(()).unsupported_below_group("a", "1.3");
+
+    match policy.aggregate_version_maps(&uris(&["b:1", "a:1.2"]), &resolver) {
+        Err(AggregateVersionMapError::UnsupportedVersionGroup(err)) => assert_eq!(
+            UnsupportedVersionGroup {
+                uri: "a:1.2".to_owned(),
+                min_supported: "1.3".to_owned(),
+            },
+            err
+        ),
+        _ => panic!("Expected UnsupportedVersionGroup"),
+    }
+    assert!(policy
+        .aggregate_version_maps(&uris(&["b:1", "a:1.10"]), &resolver)
+        .is_ok());
+}
+
+#[test]
+fn sunset_policy_reports_deprecated_versions() {
+    let resolver = version_group_resolver_new! {
+        ("a", "1") => { A => 1, },
+        ("a", "2") => { A => 2, },
+        ("a", "3") => { A => 3, },
+    };
+    let deprecated = Arc::new(Mutex::new(Vec::new()));
+    let policy = SunsetPolicy::new(CachedAggregator::default())
+        .unsupported_below::<A>(2)
+        .deprecated_below::<A>(3)
+        .on_deprecated({
+            let deprecated = deprecated.clone();
+            move |version| deprecated.lock().unwrap().push(version.clone())
+        });
+
+    assert!(policy
+        .aggregate_version_maps(&uris(&["a:1"]), &resolver)
+        .is_err());
+    assert!(policy
+        .aggregate_version_maps(&uris(&["a:2"]), &resolver)
+        .is_ok());
+    assert!(policy
+        .aggregate_version_maps(&uris(&["a:3"]), &resolver)
+        .is_ok());
+    assert_eq!(
+        vec![DeprecatedVersion {
+            type_id: std::any::type_name::<A>().to_owned(),
+            version: 2,
+            min_recommended: 3,
+        }],
+        *deprecated.lock().unwrap()
+    );
+}
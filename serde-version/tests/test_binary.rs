@@ -0,0 +1,198 @@
+#![feature(min_specialization)]
+#![cfg(feature = "bincode-support")]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[macro_use]
+extern crate lazy_static;
+
+use serde::{Deserialize, Serialize};
+use serde_version::binary::{read_header, write_header, DeserializeError, HeaderError};
+use serde_version::VersionGroupURIs;
+use std::convert::TryInto;
+
+// Not the fixture of `tests/common`: bincode does not describe the fields,
+// so the versions differ by a removed field and a wider integer
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "A")]
+struct Av1 {
+    a: u8,
+    c: String,
+}
+
+#[derive(Serialize, Deserialize, DeserializeVersioned, PartialEq, Debug)]
+#[versions(v(index = 1, type = "Av1"), v(index = 2, self))]
+struct A {
+    b: u16,
+}
+
+impl From<Av1> for A {
+    fn from(v: Av1) -> Self {
+        Self { b: v.a as u16 }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "E")]
+enum Ev1 {
+    Unit,
+}
+
+#[derive(Serialize, Deserialize, DeserializeVersioned, PartialEq, Debug)]
+#[versions(v(index = 1, type = "Ev1"), v(index = 2, self))]
+enum E {
+    Empty,
+    Value(u8),
+}
+
+impl From<Ev1> for E {
+    fn from(v: Ev1) -> Self {
+        match v {
+            Ev1::Unit => E::Empty,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Containerv1 {
+    a: Av1,
+    others: Vec<Av1>,
+    e: Ev1,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Container {
+    a: A,
+    others: Vec<A>,
+    e: E,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("core", "1") => { A => 1, E => 1, },
+        ("core", "2") => { A => 2, E => 2, },
+    }
+}
+
+fn uris(uri: &'static str) -> VersionGroupURIs<'static> {
+    vec![uri.try_into().unwrap()].into()
+}
+
+#[test]
+fn header_roundtrip_works() {
+    let uris: VersionGroupURIs = vec![
+        "core:1".try_into().unwrap(),
+        "my.plugin:1.2.0".try_into().unwrap(),
+    ]
+    .into();
+
+    let mut output = Vec::new();
+    write_header(&mut output, &uris).unwrap();
+    output.extend_from_slice(&[1, 2, 3]);
+
+    let (read, payload) = read_header(&output).unwrap();
+    assert_eq!(uris, read);
+    assert_eq!(&[1, 2, 3], payload);
+}
+
+#[test]
+fn deserialize_latest_works() {
+    let value = Container {
+        a: A { b: 300 },
+        others: vec![A { b: 1 }, A { b: 2 }],
+        e: E::Value(4),
+    };
+    let output = serde_version::binary::serialize(&value, &uris("core:2")).unwrap();
+
+    let de: Container = serde_version::binary::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(value, de);
+}
+
+#[test]
+fn deserialize_previous_version_works() {
+    let value = Containerv1 {
+        a: Av1 {
+            a: 5,
+            c: "removed".to_owned(),
+        },
+        others: vec![Av1 {
+            a: 3,
+            c: String::new(),
+        }],
+        e: Ev1::Unit,
+    };
+    let output = serde_version::binary::serialize(&value, &uris("core:1")).unwrap();
+
+    let de: Container = serde_version::binary::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        Container {
+            a: A { b: 5 },
+            others: vec![A { b: 3 }],
+            e: E::Empty,
+        },
+        de
+    );
+}
+
+#[test]
+fn deserialize_without_header_fails() {
+    let output = bincode::serialize(&A { b: 5 }).unwrap();
+
+    match serde_version::binary::deserialize::<A, _, _>(&output, &*VERSIONS, &()) {
+        Err(DeserializeError::Header(HeaderError::InvalidMagic)) => {}
+        _ => panic!("Expected InvalidMagic"),
+    }
+}
+
+#[test]
+fn read_truncated_header_fails() {
+    let mut output = Vec::new();
+    write_header(&mut output, &uris("core:2")).unwrap();
+    output.truncate(output.len() - 1);
+
+    match read_header(&output) {
+        Err(HeaderError::Truncated) => {}
+        _ => panic!("Expected Truncated"),
+    }
+}
+
+#[test]
+fn deserialize_from_reader_works() {
+    let value = Container {
+        a: A { b: 300 },
+        others: vec![A { b: 1 }],
+        e: E::Value(4),
+    };
+    let mut output = Vec::new();
+    serde_version::binary::serialize_into(
+        &mut output,
+        &Containerv1 {
+            a: Av1 {
+                a: 44,
+                c: String::new(),
+            },
+            others: vec![],
+            e: Ev1::Unit,
+        },
+        &uris("core:1"),
+    )
+    .unwrap();
+    serde_version::binary::serialize_into(&mut output, &value, &uris("core:2")).unwrap();
+
+    let mut reader = std::io::Cursor::new(output);
+    let de: Container =
+        serde_version::binary::deserialize_from_reader(&mut reader, &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        Container {
+            a: A { b: 44 },
+            others: vec![],
+            e: E::Empty,
+        },
+        de
+    );
+    let de: Container =
+        serde_version::binary::deserialize_from_reader(&mut reader, &*VERSIONS, &()).unwrap();
+    assert_eq!(value, de);
+}
@@ -0,0 +1,118 @@
+use serde_version::{
+    check_compatibility, BreakingChange, VersionGroupURI, VersionManifestGroup, VersionSnapshot,
+};
+use std::convert::TryFrom;
+
+fn group(uri: &'static str, types: &[(&str, usize)]) -> VersionManifestGroup<'static> {
+    VersionManifestGroup {
+        uri: VersionGroupURI::try_from(uri).unwrap(),
+        types: types
+            .iter()
+            .map(|(type_id, version)| ((*type_id).to_owned(), *version))
+            .collect(),
+    }
+}
+
+fn snapshot(
+    groups: Vec<VersionManifestGroup<'static>>,
+    types: &[(&str, &[usize])],
+) -> VersionSnapshot<'static> {
+    VersionSnapshot {
+        groups,
+        latest: types
+            .iter()
+            .map(|(type_id, versions)| ((*type_id).to_owned(), *versions.last().unwrap()))
+            .collect(),
+        versions: types
+            .iter()
+            .map(|(type_id, versions)| ((*type_id).to_owned(), versions.to_vec()))
+            .collect(),
+    }
+}
+
+#[test]
+fn compatible_snapshots_work() {
+    let previous = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[1])]);
+    let current = snapshot(
+        vec![group("core:1", &[("A", 1)]), group("core:2", &[("A", 2)])],
+        &[("A", &[1, 2])],
+    );
+
+    assert_eq!(
+        Vec::<BreakingChange>::new(),
+        check_compatibility(&previous, &current)
+    );
+}
+
+#[test]
+fn group_changes_are_reported() {
+    let previous = snapshot(
+        vec![group("core:1", &[("A", 1)]), group("core:2", &[("A", 1)])],
+        &[("A", &[1])],
+    );
+    let current = snapshot(vec![group("core:2", &[("A", 2)])], &[("A", &[1])]);
+
+    assert_eq!(
+        vec![
+            BreakingChange::GroupRemoved {
+                uri: "core:1".to_owned()
+            },
+            BreakingChange::GroupRedefined {
+                uri: "core:2".to_owned()
+            },
+        ],
+        check_compatibility(&previous, &current)
+    );
+}
+
+#[test]
+fn latest_version_without_group_is_reported() {
+    let previous = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[1])]);
+    let current = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[1, 2])]);
+
+    let changes = check_compatibility(&previous, &current);
+    assert_eq!(
+        vec![BreakingChange::LatestVersionWithoutGroup {
+            type_id: "A".to_owned(),
+            previous: 1,
+            current: 2,
+        }],
+        changes
+    );
+    assert_eq!(
+        "The latest version of the type A changed from 1 to 2 without a new version group",
+        changes[0].to_string()
+    );
+}
+
+#[test]
+fn removed_version_is_reported() {
+    let previous = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[1, 2])]);
+    let current = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[2])]);
+
+    assert_eq!(
+        vec![BreakingChange::VersionRemoved {
+            type_id: "A".to_owned(),
+            version: 1,
+        }],
+        check_compatibility(&previous, &current)
+    );
+}
+
+#[test]
+fn removed_type_is_reported() {
+    let previous = snapshot(
+        vec![group("core:1", &[("A", 1)])],
+        &[("A", &[1]), ("B", &[1, 2])],
+    );
+    let current = snapshot(vec![group("core:1", &[("A", 1)])], &[("A", &[1])]);
+
+    let changes = check_compatibility(&previous, &current);
+    assert_eq!(
+        vec![BreakingChange::TypeRemoved {
+            type_id: "B".to_owned(),
+        }],
+        changes
+    );
+    assert_eq!("The type B was removed", changes[0].to_string());
+}
@@ -6,10 +6,12 @@ extern crate serde_version_derive;
 #[macro_use]
 mod common;
 
-use serde::Deserialize;
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
 use serde_test::Token;
 use serde_version::{
-    DefaultVersionMap, DeserializeVersioned, InvalidVersionError, VersionedDeserializer,
+    wrap_map_access, wrap_seq_access, DefaultVersionMap, DeserializeVersioned, InvalidVersionError,
+    VersionMap, VersionedDeserializer,
 };
 use std::fmt::Debug;
 
@@ -155,3 +157,363 @@ declare_tests_versions! {
         ],
     }
 }
+
+struct PairSeed<VM>(VM);
+
+impl<'de, VM: VersionMap> DeserializeSeed<'de> for PairSeed<VM> {
+    type Value = (A, A);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct PairVisitor<VM>(VM);
+
+        impl<'de, VM: VersionMap> Visitor<'de> for PairVisitor<VM> {
+            type Value = (A, A);
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a pair of A")
+            }
+
+            fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+                let mut seq = wrap_seq_access(seq, self.0);
+                let first = seq.next_element().map_err(|err| err.into_error())?;
+                let second = seq.next_element().map_err(|err| err.into_error())?;
+                match (first, second) {
+                    (Some(first), Some(second)) => Ok((first, second)),
+                    _ => Err(serde::de::Error::custom("expected two elements")),
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple(2, PairVisitor(self.0))
+    }
+}
+
+struct EntriesSeed<VM>(VM);
+
+impl<'de, VM: VersionMap> DeserializeSeed<'de> for EntriesSeed<VM> {
+    type Value = Vec<(String, A)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct EntriesVisitor<VM>(VM);
+
+        impl<'de, VM: VersionMap> Visitor<'de> for EntriesVisitor<VM> {
+            type Value = Vec<(String, A)>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of A")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let mut map = wrap_map_access(map, self.0);
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry().map_err(|err| err.into_error())? {
+                    entries.push(entry);
+                }
+                Ok(entries)
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(self.0))
+    }
+}
+
+#[test]
+fn wrap_seq_access_works() {
+    let version_map = vec![("test_de::A", 1)]
+        .into_iter()
+        .collect::<DefaultVersionMap>();
+    let tokens = &[
+        Token::Tuple { len: 2 },
+        Token::Map { len: Some(1) },
+        Token::Str("a"),
+        Token::I32(8),
+        Token::MapEnd,
+        Token::Map { len: Some(1) },
+        Token::Str("a"),
+        Token::I32(4),
+        Token::MapEnd,
+        Token::TupleEnd,
+    ];
+
+    let mut de = serde_version_test::Deserializer::new(tokens);
+    let value = PairSeed(&version_map).deserialize(&mut de).unwrap();
+    assert_eq!((A { c: 8 }, A { c: 4 }), value);
+    assert_eq!(0, de.remaining());
+}
+
+#[test]
+fn wrap_map_access_works() {
+    let version_map = vec![("test_de::A", 3)]
+        .into_iter()
+        .collect::<DefaultVersionMap>();
+    let tokens = &[
+        Token::Map { len: Some(2) },
+        Token::Str("first"),
+        Token::Map { len: Some(1) },
+        Token::Str("b"),
+        Token::I32(8),
+        Token::MapEnd,
+        Token::Str("second"),
+        Token::Map { len: Some(0) },
+        Token::MapEnd,
+        Token::MapEnd,
+    ];
+
+    let mut de = serde_version_test::Deserializer::new(tokens);
+    let value = EntriesSeed(&version_map).deserialize(&mut de).unwrap();
+    assert_eq!(
+        vec![
+            ("first".to_owned(), A { c: 8 }),
+            ("second".to_owned(), A { c: 5 }),
+        ],
+        value
+    );
+    assert_eq!(0, de.remaining());
+}
+
+#[derive(Deserialize)]
+#[serde(rename(deserialize = "Id"))]
+struct Idv1(u8);
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(rename(deserialize = "Id"))]
+#[versions(v(index = 1, type = "Idv1"), v(index = 2, self))]
+struct Id(String);
+
+impl From<Idv1> for Id {
+    fn from(v: Idv1) -> Self {
+        Self(v.0.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename(deserialize = "Point"))]
+struct Pointv1 {
+    x: u8,
+    y: u8,
+}
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(rename(deserialize = "Point"))]
+#[versions(v(index = 1, type = "Pointv1"), v(index = 2, self))]
+struct Point(u8, u8);
+
+impl From<Pointv1> for Point {
+    fn from(v: Pointv1) -> Self {
+        Self(v.x, v.y)
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct WrapsId(#[serde(with = "serde_version::scoped")] Id);
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct WrapsA(#[serde(with = "serde_version::scoped")] A);
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(transparent)]
+struct TransparentA(#[serde(with = "serde_version::scoped")] A);
+
+#[derive(Deserialize)]
+#[serde(rename(deserialize = "Label"))]
+struct Labelv1(u8);
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(transparent)]
+#[versions(v(index = 1, type = "Labelv1"), v(index = 2, self))]
+struct Label {
+    a: A,
+}
+
+impl From<Labelv1> for Label {
+    fn from(v: Labelv1) -> Self {
+        Self { a: A { c: v.0 } }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename(deserialize = "Percent"))]
+struct Percentv1(u8);
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(transparent)]
+#[versions(v(index = 1, type = "Percentv1"), v(index = 2, self))]
+struct Percent {
+    #[serde(deserialize_with = "from_ratio")]
+    value: u8,
+}
+
+impl From<Percentv1> for Percent {
+    fn from(v: Percentv1) -> Self {
+        Self { value: v.0 }
+    }
+}
+
+fn from_ratio<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    f32::deserialize(deserializer).map(|ratio| (ratio * 100.0) as u8)
+}
+
+/// A newtype struct with a visitor that only implements `visit_newtype_struct`
+#[derive(PartialEq, Debug)]
+struct Meters(u32);
+
+impl<'de> Deserialize<'de> for Meters {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MetersVisitor;
+
+        impl<'de> Visitor<'de> for MetersVisitor {
+            type Value = Meters;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("meters")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                u32::deserialize(deserializer).map(Meters)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("Meters", MetersVisitor)
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct ContainsMeters {
+    a: A,
+    length: Meters,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct ContainsTuples {
+    id: Id,
+    point: Point,
+}
+
+declare_tests_versions! {
+    test_newtype_version ("test_de::Id" => 1, "test_de::Point" => 1, "test_de::A" => 1) {
+        Id: Id("8".to_owned()) => &[
+            Token::NewtypeStruct { name: "Id" },
+            Token::U8(8),
+        ],
+        Point: Point(1, 2) => &[
+            Token::Map { len: Some(2) },
+                Token::Str("x"),
+                Token::U8(1),
+                Token::Str("y"),
+                Token::U8(2),
+            Token::MapEnd,
+        ],
+        ContainsTuples: ContainsTuples { id: Id("8".to_owned()), point: Point(1, 2) } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("id"),
+                Token::NewtypeStruct { name: "Id" },
+                Token::U8(8),
+                Token::Str("point"),
+                Token::Map { len: Some(2) },
+                    Token::Str("x"),
+                    Token::U8(1),
+                    Token::Str("y"),
+                    Token::U8(2),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+        ContainsMeters: ContainsMeters { a: A { c: 8 }, length: Meters(3) } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("a"),
+                Token::Map { len: Some(1) },
+                    Token::Str("a"),
+                    Token::I32(8),
+                Token::MapEnd,
+                Token::Str("length"),
+                Token::NewtypeStruct { name: "Meters" },
+                Token::U32(3),
+            Token::MapEnd,
+        ],
+    }
+    scoped test_newtype_helper_version ("test_de::Id" => 1, "test_de::A" => 1) {
+        WrapsId: WrapsId(Id("8".to_owned())) => &[
+            Token::NewtypeStruct { name: "WrapsId" },
+            Token::NewtypeStruct { name: "Id" },
+            Token::U8(8),
+        ],
+        WrapsA: WrapsA(A { c: 8 }) => &[
+            Token::NewtypeStruct { name: "WrapsA" },
+            Token::Map { len: Some(1) },
+                Token::Str("a"),
+                Token::I32(8),
+            Token::MapEnd,
+        ],
+        TransparentA: TransparentA(A { c: 8 }) => &[
+            Token::Map { len: Some(1) },
+                Token::Str("a"),
+                Token::I32(8),
+            Token::MapEnd,
+        ],
+    }
+    test_newtype_current_version ("test_de::Id" => 2, "test_de::Point" => 2, "test_de::A" => 4) {
+        ContainsTuples: ContainsTuples { id: Id("8".to_owned()), point: Point(1, 2) } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("id"),
+                Token::NewtypeStruct { name: "Id" },
+                Token::Str("8"),
+                Token::Str("point"),
+                Token::TupleStruct { name: "Point", len: 2 },
+                    Token::U8(1),
+                    Token::U8(2),
+                Token::TupleStructEnd,
+            Token::MapEnd,
+        ],
+        Meters: Meters(3) => &[
+            Token::NewtypeStruct { name: "Meters" },
+            Token::U32(3),
+        ],
+    }
+    scoped test_newtype_helper_current_version ("test_de::Id" => 2, "test_de::A" => 4) {
+        WrapsId: WrapsId(Id("8".to_owned())) => &[
+            Token::NewtypeStruct { name: "WrapsId" },
+            Token::NewtypeStruct { name: "Id" },
+            Token::Str("8"),
+        ],
+        WrapsA: WrapsA(A { c: 8 }) => &[
+            Token::NewtypeStruct { name: "WrapsA" },
+            Token::Map { len: Some(1) },
+                Token::Str("c"),
+                Token::I32(8),
+            Token::MapEnd,
+        ],
+    }
+    test_transparent_version ("test_de::Label" => 1) {
+        Label: Label { a: A { c: 8 } } => &[
+            Token::NewtypeStruct { name: "Label" },
+            Token::U8(8),
+        ],
+    }
+    test_transparent_with_attribute_version ("test_de::Percent" => 1) {
+        Percent: Percent { value: 50 } => &[
+            Token::NewtypeStruct { name: "Percent" },
+            Token::U8(50),
+        ],
+    }
+    test_transparent_with_attribute_current_version ("test_de::Percent" => 2) {
+        Percent: Percent { value: 50 } => &[
+            Token::F32(0.5),
+        ],
+    }
+    test_transparent_current_version ("test_de::Label" => 2, "test_de::A" => 1) {
+        Label: Label { a: A { c: 8 } } => &[
+            Token::Map { len: Some(1) },
+                Token::Str("a"),
+                Token::I32(8),
+            Token::MapEnd,
+        ],
+    }
+}
@@ -0,0 +1,155 @@
+#![feature(min_specialization)]
+#![cfg(any(feature = "msgpack-support", feature = "cbor-support"))]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[allow(unused_macros)]
+mod common;
+#[macro_use]
+extern crate lazy_static;
+
+use common::{Av1, A};
+use serde::{Deserialize, Serialize};
+use serde_version::{VersionGroupURIs, VersionRegistry};
+use std::convert::TryInto;
+
+#[derive(Serialize)]
+struct Telemetryv1 {
+    device: String,
+    a: Av1,
+    readings: Vec<Av1>,
+}
+
+#[derive(Serialize, Deserialize, VersionedTypes, PartialEq, Debug)]
+struct Telemetry {
+    device: String,
+    a: A,
+    readings: Vec<A>,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("firmware", "1") => { A => 1, },
+        ("firmware", "2") => { A => 2, },
+    }
+}
+
+fn uris(uri: &'static str) -> VersionGroupURIs<'static> {
+    vec![uri.try_into().unwrap()].into()
+}
+
+fn registry() -> VersionRegistry {
+    let registry = VersionRegistry::new();
+    registry
+        .register(
+            &"firmware:1".try_into().unwrap(),
+            version_map_new! { A => 1, },
+        )
+        .unwrap();
+    registry
+        .register(
+            &"firmware:2".try_into().unwrap(),
+            version_map_new! { A => 2, },
+        )
+        .unwrap();
+    registry
+}
+
+fn previous() -> Telemetryv1 {
+    Telemetryv1 {
+        device: "sensor".to_owned(),
+        a: Av1 { a: 5 },
+        readings: vec![Av1 { a: 3 }],
+    }
+}
+
+fn expected() -> Telemetry {
+    Telemetry {
+        device: "sensor".to_owned(),
+        a: A { b: 5 },
+        readings: vec![A { b: 3 }],
+    }
+}
+
+#[cfg(feature = "msgpack-support")]
+#[test]
+fn msgpack_deserialize_works() {
+    let output = serde_version::msgpack::serialize(&previous(), &uris("firmware:1")).unwrap();
+    let de: Telemetry = serde_version::msgpack::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+
+    let output = serde_version::msgpack::serialize(&expected(), &uris("firmware:2")).unwrap();
+    let de: Telemetry = serde_version::msgpack::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[cfg(feature = "msgpack-support")]
+#[test]
+fn msgpack_deserialize_from_reader_works() {
+    let mut output = Vec::new();
+    serde_version::msgpack::serialize_into(&mut output, &previous(), &uris("firmware:1")).unwrap();
+    serde_version::msgpack::serialize_into(&mut output, &expected(), &uris("firmware:2")).unwrap();
+
+    let mut reader = std::io::Cursor::new(output);
+    for _ in 0..2 {
+        let de: Telemetry =
+            serde_version::msgpack::deserialize_from_reader(&mut reader, &*VERSIONS, &()).unwrap();
+        assert_eq!(expected(), de);
+    }
+}
+
+#[cfg(feature = "msgpack-support")]
+#[test]
+fn msgpack_serialize_latest_works() {
+    let output = serde_version::msgpack::serialize_latest(&expected(), &registry()).unwrap();
+    assert_eq!(
+        serde_version::msgpack::serialize(&expected(), &uris("firmware:2")).unwrap(),
+        output
+    );
+}
+
+#[cfg(feature = "cbor-support")]
+#[test]
+fn cbor_deserialize_works() {
+    let output = serde_version::cbor::serialize(&previous(), &uris("firmware:1")).unwrap();
+    let de: Telemetry = serde_version::cbor::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+
+    let output = serde_version::cbor::serialize(&expected(), &uris("firmware:2")).unwrap();
+    let de: Telemetry = serde_version::cbor::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[cfg(feature = "cbor-support")]
+#[test]
+fn cbor_deserialize_from_reader_works() {
+    let mut output = Vec::new();
+    serde_version::cbor::serialize_into(&mut output, &previous(), &uris("firmware:1")).unwrap();
+    serde_version::cbor::serialize_into(&mut output, &expected(), &uris("firmware:2")).unwrap();
+
+    let mut reader = std::io::Cursor::new(output);
+    for _ in 0..2 {
+        let de: Telemetry =
+            serde_version::cbor::deserialize_from_reader(&mut reader, &*VERSIONS, &()).unwrap();
+        assert_eq!(expected(), de);
+    }
+}
+
+#[cfg(feature = "cbor-support")]
+#[test]
+fn cbor_deserialize_unknown_version_fails() {
+    let output = serde_version::cbor::serialize(&previous(), &uris("firmware:3")).unwrap();
+    assert!(serde_version::cbor::deserialize::<Telemetry, _, _>(&output, &*VERSIONS, &()).is_err());
+}
+
+#[cfg(feature = "cbor-support")]
+#[test]
+fn cbor_serialize_latest_works() {
+    let output = serde_version::cbor::serialize_latest(&expected(), &registry()).unwrap();
+    assert_eq!(
+        serde_version::cbor::serialize(&expected(), &uris("firmware:2")).unwrap(),
+        output
+    );
+}
@@ -0,0 +1,133 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version_derive;
+
+#[macro_use]
+mod common;
+
+use common::A;
+use serde::Deserialize;
+use serde_test::Token;
+use serde_version::DefaultVersionMap;
+use std::fmt::Debug;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Shared {
+    name: String,
+    a: A,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct FlattenA {
+    value: u8,
+    #[serde(flatten, deserialize_with = "serde_version::scoped::deserialize")]
+    a: A,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct FlattenShared {
+    value: u8,
+    #[serde(flatten, deserialize_with = "serde_version::scoped::deserialize")]
+    shared: Shared,
+}
+
+declare_tests_versions! {
+    scoped test_flatten_version (std::any::type_name::<A>() => 1) {
+        FlattenA: FlattenA { value: 3, a: A { b: 8 } } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("value"),
+                Token::U8(3),
+                Token::Str("a"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+        FlattenShared: FlattenShared { value: 3, shared: Shared { name: "n".to_owned(), a: A { b: 8 } } } => &[
+            Token::Map { len: Some(3) },
+                Token::Str("value"),
+                Token::U8(3),
+                Token::Str("name"),
+                Token::Str("n"),
+                Token::Str("a"),
+                Token::Map { len: Some(1) },
+                    Token::Str("a"),
+                    Token::U8(8),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+    }
+    scoped test_flatten_current_version (std::any::type_name::<A>() => 2) {
+        FlattenA: FlattenA { value: 3, a: A { b: 8 } } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("value"),
+                Token::U8(3),
+                Token::Str("b"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+        FlattenShared: FlattenShared { value: 3, shared: Shared { name: "n".to_owned(), a: A { b: 8 } } } => &[
+            Token::Map { len: Some(3) },
+                Token::Str("value"),
+                Token::U8(3),
+                Token::Str("name"),
+                Token::Str("n"),
+                Token::Str("a"),
+                Token::Map { len: Some(1) },
+                    Token::Str("b"),
+                    Token::U8(8),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+    }
+}
+
+#[test]
+fn flatten_without_scope_ignores_versions() {
+    let tokens = &[
+        Token::Map { len: Some(2) },
+        Token::Str("value"),
+        Token::U8(3),
+        Token::Str("b"),
+        Token::U8(8),
+        Token::MapEnd,
+    ];
+    let mut de = serde_version_test::Deserializer::new(tokens);
+    assert_eq!(
+        FlattenA {
+            value: 3,
+            a: A { b: 8 }
+        },
+        FlattenA::deserialize(&mut de).unwrap()
+    );
+}
+
+#[test]
+fn flatten_uses_innermost_scope() {
+    let tokens = &[
+        Token::Map { len: Some(2) },
+        Token::Str("value"),
+        Token::U8(3),
+        Token::Str("a"),
+        Token::U8(8),
+        Token::MapEnd,
+    ];
+    let outer: std::collections::HashMap<&str, usize> =
+        vec![(std::any::type_name::<A>(), 2)].into_iter().collect();
+    let inner: std::collections::HashMap<&str, usize> =
+        vec![(std::any::type_name::<A>(), 1)].into_iter().collect();
+    let value = serde_version::scope(&outer, || {
+        let value = serde_version::scope(&inner, || {
+            FlattenA::deserialize(&mut serde_version_test::Deserializer::new(tokens))
+        });
+        // The outer scope is restored
+        assert!(FlattenA::deserialize(&mut serde_version_test::Deserializer::new(tokens)).is_err());
+        value
+    });
+    assert_eq!(
+        FlattenA {
+            value: 3,
+            a: A { b: 8 }
+        },
+        value.unwrap()
+    );
+}
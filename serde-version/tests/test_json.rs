@@ -0,0 +1,290 @@
+#![feature(min_specialization)]
+#![cfg(feature = "json-support")]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[allow(unused_macros)]
+mod common;
+#[macro_use]
+extern crate lazy_static;
+
+use common::A;
+use serde::{Deserialize, Serialize};
+use serde_version::json::{ReadRecordError, RecordError};
+use serde_version::value::DeserializeValueError;
+use serde_version::{
+    AggregateVersionMap, AggregateVersionMapError, CachedAggregator, VersionGroupResolver,
+    VersionGroupURI, VersionGroupURIs, VersionMap, VersionRegistry,
+};
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Serialize, Deserialize, VersionedTypes, PartialEq, Debug)]
+struct Export {
+    name: String,
+    rows: Vec<A>,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("export", "1") => { A => 1, },
+        ("export", "2") => { A => 2, },
+    }
+}
+
+fn uris(uri: &'static str) -> VersionGroupURIs<'static> {
+    vec![uri.try_into().unwrap()].into()
+}
+
+fn expected() -> Export {
+    Export {
+        name: "export".to_owned(),
+        rows: vec![A { b: 5 }, A { b: 3 }],
+    }
+}
+
+#[test]
+fn deserialize_works() {
+    let input = r#"{"v":["export:1"]}
+{"name":"export","rows":[{"a":5},{"a":3}]}
+"#;
+    let de: Export = serde_version::json::deserialize(input, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[test]
+fn serialize_works() {
+    let output = serde_version::json::serialize(&expected(), &uris("export:2")).unwrap();
+    assert_eq!(
+        r#"{"v":["export:2"]}
+{"name":"export","rows":[{"b":5},{"b":3}]}
+"#,
+        output
+    );
+}
+
+#[test]
+fn serialize_latest_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(
+            &"export:1".try_into().unwrap(),
+            version_map_new! { A => 1, },
+        )
+        .unwrap();
+    registry
+        .register(
+            &"export:2".try_into().unwrap(),
+            version_map_new! { A => 2, },
+        )
+        .unwrap();
+
+    let output = serde_version::json::serialize_latest(&expected(), &registry).unwrap();
+    assert_eq!(
+        serde_version::json::serialize(&expected(), &uris("export:2")).unwrap(),
+        output
+    );
+}
+
+#[test]
+fn deserialize_from_reader_works() {
+    let mut output = Vec::new();
+    serde_version::json::serialize_into(&mut output, &expected(), &uris("export:2")).unwrap();
+    output.extend_from_slice(
+        br#"{"v":["export:1"]}
+{"name":"export","rows":[{"a":5},{"a":3}]}
+"#,
+    );
+
+    let mut reader = std::io::BufReader::new(std::io::Cursor::new(output));
+    for _ in 0..2 {
+        let de: Export =
+            serde_version::json::deserialize_from_reader(&mut reader, &*VERSIONS, &()).unwrap();
+        assert_eq!(expected(), de);
+    }
+}
+
+#[test]
+fn deserialize_with_trailing_characters_fails() {
+    let input = r#"{"v":["export:2"]} {"name":"export","rows":[]} {}"#;
+    assert!(serde_version::json::deserialize::<Export, _, _>(input, &*VERSIONS, &()).is_err());
+}
+
+#[test]
+fn records_works() {
+    let input = r#"{"v":["export:1"]}
+{"a":1}
+{"a":2}
+
+{"v":["export:2"],"b":3}
+{"v":["export:2"]}
+{"b":4}
+"#;
+    let aggregator = CachedAggregator::default();
+    let records = serde_version::json::records(input.as_bytes(), &*VERSIONS, &aggregator)
+        .collect::<Result<Vec<A>, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![A { b: 1 }, A { b: 2 }, A { b: 3 }, A { b: 4 }],
+        records
+    );
+}
+
+#[test]
+fn records_without_version_tag_use_the_header_line() {
+    let input = r#"{"v":["export:1"]}
+{"name":"first","rows":[{"a":5}]}
+{"v":["export:2"]}
+{"name":"second","rows":[{"b":3}]}
+"#;
+    let records = serde_version::json::records(input.as_bytes(), &*VERSIONS, &())
+        .collect::<Result<Vec<Export>, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![
+            Export {
+                name: "first".to_owned(),
+                rows: vec![A { b: 5 }],
+            },
+            Export {
+                name: "second".to_owned(),
+                rows: vec![A { b: 3 }],
+            },
+        ],
+        records
+    );
+}
+
+/// Count the aggregated version headers
+#[derive(Default)]
+struct CountingAggregator(AtomicUsize);
+
+impl AggregateVersionMap for CountingAggregator {
+    type VM = <() as AggregateVersionMap>::VM;
+
+    fn aggregate_version_maps<'a, VMR: VersionGroupResolver>(
+        &self,
+        uris: &[VersionGroupURI<'a>],
+        resolver: &VMR,
+    ) -> Result<Self::VM, AggregateVersionMapError>
+    where
+        VMR::VM: VersionMap,
+    {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        ().aggregate_version_maps(uris, resolver)
+    }
+}
+
+#[test]
+fn records_aggregate_the_header_line_once() {
+    let input = r#"{"v":["export:1"]}
+{"a":1}
+{"a":2}
+{"v":["export:2"],"b":3}
+{"a":4,"note":"v"}
+{"\u0076":["export:2"],"b":5}
+"#;
+    let aggregator = CountingAggregator::default();
+    let records = serde_version::json::records(input.as_bytes(), &*VERSIONS, &aggregator)
+        .collect::<Result<Vec<A>, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![A { b: 1 }, A { b: 2 }, A { b: 3 }, A { b: 4 }, A { b: 5 }],
+        records
+    );
+    // The header line once, and each record with an inline version tag
+    assert_eq!(3, aggregator.0.load(Ordering::SeqCst));
+}
+
+#[test]
+fn records_report_the_failed_header_line() {
+    let input = r#"{"v":["export:1"]}
+{"a":1}
+{"v":["unknown:1"]}
+{"a":2}
+"#;
+    let mut records = serde_version::json::records::<_, A, _, _>(input.as_bytes(), &*VERSIONS, &());
+    assert_eq!(A { b: 1 }, records.next().unwrap().unwrap());
+    match records.next() {
+        Some(Err(RecordError {
+            line: 3,
+            error: ReadRecordError::Value(DeserializeValueError::AggregateError(_)),
+        })) => {}
+        _ => panic!("Expected AggregateError"),
+    }
+    match records.next() {
+        Some(Err(RecordError {
+            line: 4,
+            error: ReadRecordError::Value(DeserializeValueError::MissingVersionHeader),
+        })) => {}
+        _ => panic!("Expected MissingVersionHeader"),
+    }
+}
+
+#[test]
+fn records_reports_the_failed_line() {
+    let input = r#"{"a":1}
+{"v":["export:1"]}
+{"a":
+{"a":2}
+"#;
+    let mut records = serde_version::json::records::<_, A, _, _>(input.as_bytes(), &*VERSIONS, &());
+    match records.next() {
+        Some(Err(RecordError {
+            line: 1,
+            error: ReadRecordError::Value(DeserializeValueError::MissingVersionHeader),
+        })) => {}
+        _ => panic!("Expected MissingVersionHeader"),
+    }
+    match records.next() {
+        Some(Err(RecordError {
+            line: 3,
+            error: ReadRecordError::Json(_),
+        })) => {}
+        _ => panic!("Expected Json"),
+    }
+    assert_eq!(A { b: 2 }, records.next().unwrap().unwrap());
+    assert!(records.next().is_none());
+}
+
+#[test]
+fn migrate_file_latest_works() {
+    let dir = std::env::temp_dir().join(format!("serde_version_json_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("export.json");
+    let original = r#"{"v":["export:1"]}
+{"name":"export","rows":[{"a":5},{"a":3}]}
+"#;
+    let migrated = r#"{"v":["export:2"]}
+{"name":"export","rows":[{"b":5},{"b":3}]}
+"#;
+    std::fs::write(&path, original).unwrap();
+
+    let registry = VersionRegistry::new();
+    registry
+        .register(
+            &"export:1".try_into().unwrap(),
+            version_map_new! { A => 1, },
+        )
+        .unwrap();
+    registry
+        .register(
+            &"export:2".try_into().unwrap(),
+            version_map_new! { A => 2, },
+        )
+        .unwrap();
+
+    let migration =
+        serde_version::json::migrate_file_latest::<Export, _>(&path, &registry, &(), true).unwrap();
+    assert_eq!(migrated, migration.migrated);
+    assert_eq!(original, std::fs::read_to_string(&path).unwrap());
+
+    serde_version::json::migrate_file_latest::<Export, _>(&path, &registry, &(), false).unwrap();
+    assert_eq!(migrated, std::fs::read_to_string(&path).unwrap());
+    // The temporary file was renamed
+    assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
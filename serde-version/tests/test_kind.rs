@@ -0,0 +1,123 @@
+#![feature(min_specialization)]
+#![cfg(feature = "json-support")]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[macro_use]
+extern crate lazy_static;
+
+use serde::Deserialize;
+use serde_json::json;
+use serde_version::kind::{KindError, KindRegistry};
+
+#[derive(Deserialize)]
+#[serde(rename = "Deployment")]
+struct Deploymentv1 {
+    replicas: u32,
+}
+
+#[derive(Deserialize, DeserializeVersioned, PartialEq, Debug)]
+#[versions(v(index = 1, type = "Deploymentv1"), v(index = 2, self))]
+struct Deployment {
+    min_replicas: u32,
+}
+
+impl From<Deploymentv1> for Deployment {
+    fn from(v: Deploymentv1) -> Self {
+        Self {
+            min_replicas: v.replicas,
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct ConfigMap {
+    data: Vec<String>,
+}
+
+#[derive(PartialEq, Debug)]
+enum Object {
+    Deployment(Deployment),
+    ConfigMap(ConfigMap),
+}
+
+impl From<Deployment> for Object {
+    fn from(v: Deployment) -> Self {
+        Object::Deployment(v)
+    }
+}
+
+impl From<ConfigMap> for Object {
+    fn from(v: ConfigMap) -> Self {
+        Object::ConfigMap(v)
+    }
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("apps", "v1") => { Deployment => 1, },
+        ("apps", "v2") => { Deployment => 2, },
+        ("core", "v1") => { ConfigMap => 1, },
+    }
+}
+
+fn kinds() -> KindRegistry<serde_json::Value, Object> {
+    let mut kinds = KindRegistry::new();
+    kinds
+        .register::<Deployment>("apps", "Deployment")
+        .register::<ConfigMap>("core", "ConfigMap");
+    kinds
+}
+
+#[test]
+fn load_all_works() {
+    let documents = vec![
+        json!({ "apiVersion": "apps/v1", "kind": "Deployment", "replicas": 3 }),
+        json!({ "apiVersion": "apps/v2", "kind": "Deployment", "min_replicas": 2 }),
+        json!({ "apiVersion": "v1", "kind": "ConfigMap", "data": ["a"] }),
+    ];
+
+    let objects = kinds().load_all(documents, &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        vec![
+            Object::Deployment(Deployment { min_replicas: 3 }),
+            Object::Deployment(Deployment { min_replicas: 2 }),
+            Object::ConfigMap(ConfigMap {
+                data: vec!["a".to_owned()]
+            }),
+        ],
+        objects
+    );
+}
+
+#[test]
+fn load_without_kind_fails() {
+    let document = json!({ "apiVersion": "apps/v1", "replicas": 3 });
+    match kinds().load(document, &*VERSIONS, &()) {
+        Err(KindError::MissingEntry { key: "kind" }) => {}
+        _ => panic!("Expected MissingEntry"),
+    }
+}
+
+#[test]
+fn load_unknown_kind_fails() {
+    let document = json!({ "apiVersion": "apps/v1", "kind": "StatefulSet" });
+    match kinds().load(document, &*VERSIONS, &()) {
+        Err(KindError::UnknownKind { api_group, kind }) => {
+            assert_eq!("apps", api_group);
+            assert_eq!("StatefulSet", kind);
+        }
+        _ => panic!("Expected UnknownKind"),
+    }
+}
+
+#[test]
+fn load_unknown_api_version_fails() {
+    let document = json!({ "apiVersion": "apps/v3", "kind": "Deployment", "min_replicas": 1 });
+    match kinds().load(document, &*VERSIONS, &()) {
+        Err(KindError::AggregateError(_)) => {}
+        _ => panic!("Expected AggregateError"),
+    }
+}
@@ -0,0 +1,135 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+
+use serde::Deserialize;
+use serde_version::{
+    latest_uris, latest_version_map, DefaultVersionMap, VersionGroupURI, VersionRegistry,
+};
+use std::convert::TryFrom;
+
+#[derive(Deserialize, DeserializeVersioned)]
+#[versions(v(index = 1, self))]
+struct A;
+
+#[derive(Deserialize, DeserializeVersioned)]
+#[versions(v(index = 1, type = "Bv1"), v(index = 3, self))]
+#[allow(dead_code)]
+struct B {
+    a: Vec<A>,
+    children: Option<Box<B>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "B")]
+struct Bv1 {}
+
+impl From<Bv1> for B {
+    fn from(_: Bv1) -> Self {
+        Self {
+            a: Vec::new(),
+            children: None,
+        }
+    }
+}
+
+#[derive(Deserialize, DeserializeVersioned)]
+#[versions(v(index = 2, self))]
+struct C;
+
+#[derive(VersionedTypes)]
+#[allow(dead_code)]
+struct Container {
+    b: B,
+    c: (u8, C),
+    containers: Vec<Container>,
+}
+
+fn uri(uri: &'static str) -> VersionGroupURI<'static> {
+    VersionGroupURI::try_from(uri).unwrap()
+}
+
+#[test]
+fn latest_version_map_works() {
+    let expected: DefaultVersionMap = version_map_new! { A => 1, B => 3, C => 2, };
+    assert_eq!(expected, latest_version_map::<Container>());
+
+    let expected: DefaultVersionMap = version_map_new! { A => 1, B => 3, };
+    assert_eq!(expected, latest_version_map::<Vec<B>>());
+
+    assert_eq!(DefaultVersionMap::new(), latest_version_map::<String>());
+}
+
+#[test]
+fn latest_uris_selects_few_groups() {
+    let groups = vec![
+        (uri("a:1"), version_map_new! { A => 1, }),
+        (uri("b:1"), version_map_new! { B => 1, }),
+        (uri("b:3"), version_map_new! { B => 3, }),
+        (uri("core:2"), version_map_new! { A => 1, B => 3, C => 2, }),
+        (uri("core:1"), version_map_new! { A => 1, B => 1, C => 2, }),
+    ];
+
+    let uris = latest_uris::<Container, _, _>(groups).unwrap();
+    assert_eq!(vec![uri("core:2")], uris.to_vec());
+}
+
+#[test]
+fn latest_uris_combines_groups() {
+    let groups = vec![
+        (uri("c:2"), version_map_new! { C => 2, }),
+        (uri("b:3"), version_map_new! { A => 1, B => 3, }),
+        (uri("a:1"), version_map_new! { A => 1, }),
+    ];
+
+    let uris = latest_uris::<Container, _, _>(groups).unwrap();
+    assert_eq!(vec![uri("b:3"), uri("c:2")], uris.to_vec());
+}
+
+#[test]
+fn latest_uris_selects_the_smallest_cover() {
+    // Selecting `ab:1`, one of the largest groups, leaves `C` uncovered
+    let groups = vec![
+        (uri("ab:1"), version_map_new! { A => 1, B => 3, }),
+        (uri("bc:1"), version_map_new! { B => 3, C => 2, }),
+        (uri("a:1"), version_map_new! { A => 1, }),
+        (uri("b:3"), version_map_new! { B => 3, }),
+    ];
+
+    let uris = latest_uris::<Container, _, _>(groups).unwrap();
+    assert_eq!(vec![uri("a:1"), uri("bc:1")], uris.to_vec());
+}
+
+#[test]
+fn latest_uris_without_latest_group_fails() {
+    let groups = vec![
+        (uri("a:1"), version_map_new! { A => 1, }),
+        (uri("b:1"), version_map_new! { B => 1, C => 2, }),
+    ];
+
+    let err = latest_uris::<Container, _, _>(groups).unwrap_err();
+    assert_eq!(
+        format!(
+            "No version group defines the version 3 of the type {}",
+            std::any::type_name::<B>()
+        ),
+        err.to_string()
+    );
+}
+
+#[test]
+fn registry_latest_uris_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, B => 1, })
+        .unwrap();
+    registry
+        .register(&uri("core:2"), version_map_new! { A => 1, B => 3, })
+        .unwrap();
+
+    let uris = registry.latest_uris::<Vec<B>>().unwrap();
+    assert_eq!(vec![uri("core:2")], uris.to_vec());
+}
@@ -0,0 +1,116 @@
+#[macro_use]
+extern crate serde_version;
+
+use serde_version::{
+    KnownTypes, ManifestError, RegisterVersionGroupError, VersionGroupURI, VersionManifest,
+    VersionManifestGroup, VersionRegistry,
+};
+use std::convert::TryFrom;
+
+struct A;
+struct B;
+
+fn group(uri: &'static str, types: &[(&str, usize)]) -> VersionManifestGroup<'static> {
+    VersionManifestGroup {
+        uri: VersionGroupURI::try_from(uri).unwrap(),
+        types: types
+            .iter()
+            .map(|(type_id, version)| ((*type_id).to_owned(), *version))
+            .collect(),
+    }
+}
+
+#[test]
+fn manifest_to_registry_works() {
+    let manifest = VersionManifest {
+        groups: vec![
+            group("core:1", &[(std::any::type_name::<A>(), 1)]),
+            group(
+                "core:2",
+                &[
+                    (std::any::type_name::<A>(), 2),
+                    (std::any::type_name::<B>(), 1),
+                ],
+            ),
+        ],
+    };
+
+    let registry = manifest.to_registry(&known_types! { A, B, }).unwrap();
+    let version_map = registry
+        .get(&VersionGroupURI::try_from("core:2").unwrap())
+        .unwrap();
+    assert_eq!(Some(&2), version_map.get(std::any::type_name::<A>()));
+    assert_eq!(Some(&1), version_map.get(std::any::type_name::<B>()));
+}
+
+#[test]
+fn manifest_with_unknown_type_registers_nothing() {
+    let manifest = VersionManifest {
+        groups: vec![
+            group("core:1", &[(std::any::type_name::<A>(), 1)]),
+            group("core:2", &[("my_crate::Unknown", 1)]),
+        ],
+    };
+
+    let registry = VersionRegistry::new();
+    match manifest.register(&registry, &known_types! { A, B, }) {
+        Err(ManifestError::UnknownTypeId(_)) => {}
+        _ => panic!("Expected UnknownTypeId"),
+    }
+    assert!(registry.uris().is_empty());
+}
+
+#[test]
+fn manifest_with_conflicting_group_registers_nothing() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(
+            &VersionGroupURI::try_from("plugin:1").unwrap(),
+            version_map_new! { B => 1, },
+        )
+        .unwrap();
+    let manifest = VersionManifest {
+        groups: vec![
+            group("core:1", &[(std::any::type_name::<A>(), 1)]),
+            group("core:2", &[(std::any::type_name::<B>(), 2)]),
+        ],
+    };
+
+    match manifest.register(&registry, &known_types! { A, B, }) {
+        Err(ManifestError::Register(RegisterVersionGroupError::TypeVersionMismatch(_))) => {}
+        _ => panic!("Expected TypeVersionMismatch"),
+    }
+    assert_eq!(
+        vec![VersionGroupURI::try_from("plugin:1").unwrap()],
+        registry.uris()
+    );
+}
+
+#[test]
+fn known_types_works() {
+    let mut known_types = KnownTypes::new();
+    known_types.insert::<A>();
+
+    assert!(known_types.contains(std::any::type_name::<A>()));
+    assert!(!known_types.contains(std::any::type_name::<B>()));
+    assert_eq!(
+        known_types,
+        vec![std::any::type_name::<A>()].into_iter().collect()
+    );
+}
+
+#[test]
+fn manifest_with_aliases_works() {
+    let manifest = VersionManifest {
+        groups: vec![group("core:1", &[("core.A", 2), ("core.B", 1)])],
+    };
+
+    let registry = manifest
+        .to_registry(&known_types! { A as "core.A", B as "core.B", })
+        .unwrap();
+    let version_map = registry
+        .get(&VersionGroupURI::try_from("core:1").unwrap())
+        .unwrap();
+    assert_eq!(Some(&2), version_map.get(std::any::type_name::<A>()));
+    assert_eq!(Some(&1), version_map.get(std::any::type_name::<B>()));
+}
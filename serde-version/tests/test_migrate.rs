@@ -0,0 +1,79 @@
+use serde_version::migrate::Migration;
+
+fn migration(original: &[&str], migrated: &[&str]) -> Migration {
+    Migration {
+        original: original.iter().map(|line| format!("{}\n", line)).collect(),
+        migrated: migrated.iter().map(|line| format!("{}\n", line)).collect(),
+    }
+}
+
+/// The lines of a side of the diff, and the number of unchanged lines
+fn side(diff: &str, removed: char) -> (Vec<&str>, usize) {
+    let mut unchanged = 0;
+    let lines = diff
+        .lines()
+        .filter(|line| !line.starts_with(removed))
+        .inspect(|line| {
+            if line.starts_with(' ') {
+                unchanged += 1
+            }
+        })
+        .map(|line| &line[1..])
+        .collect();
+    (lines, unchanged)
+}
+
+fn lcs_len(original: &[&str], migrated: &[&str]) -> usize {
+    let mut lcs = vec![vec![0; migrated.len() + 1]; original.len() + 1];
+    for i in 0..original.len() {
+        for j in 0..migrated.len() {
+            lcs[i + 1][j + 1] = if original[i] == migrated[j] {
+                lcs[i][j] + 1
+            } else {
+                lcs[i][j + 1].max(lcs[i + 1][j])
+            };
+        }
+    }
+    lcs[original.len()][migrated.len()]
+}
+
+#[test]
+fn diff_works() {
+    assert_eq!(
+        " [a]\n-v = 1\n+v = 2\n x = 1\n-y = 2\n z = 3\n+w = 4\n",
+        migration(
+            &["[a]", "v = 1", "x = 1", "y = 2", "z = 3"],
+            &["[a]", "v = 2", "x = 1", "z = 3", "w = 4"]
+        )
+        .diff()
+    );
+    assert_eq!("", migration(&[], &[]).diff());
+    assert_eq!("+a\n", migration(&[], &["a"]).diff());
+    assert_eq!("-a\n", migration(&["a"], &[]).diff());
+}
+
+#[test]
+fn diff_is_minimal() {
+    let words = ["a", "b", "c", "d"];
+    // A deterministic pseudo-random generator
+    let mut seed = 7u32;
+    let mut next = move || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (seed >> 16) as usize
+    };
+    for _ in 0..200 {
+        let original = (0..next() % 12)
+            .map(|_| words[next() % words.len()])
+            .collect::<Vec<_>>();
+        let migrated = (0..next() % 12)
+            .map(|_| words[next() % words.len()])
+            .collect::<Vec<_>>();
+
+        let diff = migration(&original, &migrated).diff();
+        let (original_lines, unchanged) = side(&diff, '+');
+        let (migrated_lines, _) = side(&diff, '-');
+        assert_eq!(original, original_lines, "{}", diff);
+        assert_eq!(migrated, migrated_lines, "{}", diff);
+        assert_eq!(lcs_len(&original, &migrated), unchanged, "{}", diff);
+    }
+}
@@ -0,0 +1,73 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version_derive;
+
+#[allow(unused_macros)]
+mod common;
+
+use common::{Container, A};
+use serde_test::Token;
+use serde_version::observer::{clear_migration_observer, set_migration_observer};
+use serde_version::{DefaultVersionMap, DeserializeVersioned, VersionedDeserializer};
+use std::sync::{Arc, Mutex};
+
+fn deserialize(version: usize, field: &'static str) -> Container {
+    let tokens = [
+        Token::Map { len: Some(2) },
+        Token::Str("a"),
+        Token::Map { len: Some(1) },
+        Token::Str(field),
+        Token::U8(1),
+        Token::MapEnd,
+        Token::Str("others"),
+        Token::Seq { len: Some(1) },
+        Token::Map { len: Some(1) },
+        Token::Str(field),
+        Token::U8(2),
+        Token::MapEnd,
+        Token::SeqEnd,
+        Token::MapEnd,
+    ];
+    let version_map: DefaultVersionMap = vec![(std::any::type_name::<A>(), version)]
+        .into_iter()
+        .collect();
+
+    let mut de = serde_version_test::Deserializer::new(&tokens);
+    let de = VersionedDeserializer::new(&mut de, &version_map);
+    Container::deserialize_versioned(de, &version_map).unwrap()
+}
+
+// The observer is global, so all the checks are done in a single test
+#[test]
+fn observer_is_notified_of_migrations() {
+    let migrations = Arc::new(Mutex::new(Vec::new()));
+    set_migration_observer({
+        let migrations = migrations.clone();
+        move |type_id: &str, from, to| {
+            migrations
+                .lock()
+                .unwrap()
+                .push((type_id.to_owned(), from, to))
+        }
+    });
+
+    let expected = Container {
+        a: A { b: 1 },
+        others: vec![A { b: 2 }],
+    };
+    assert_eq!(expected, deserialize(1, "a"));
+    let migration = (std::any::type_name::<A>().to_owned(), 1, 2);
+    assert_eq!(
+        vec![migration.clone(), migration],
+        *migrations.lock().unwrap()
+    );
+
+    migrations.lock().unwrap().clear();
+    assert_eq!(expected, deserialize(2, "b"));
+    assert!(migrations.lock().unwrap().is_empty());
+
+    clear_migration_observer();
+    assert_eq!(expected, deserialize(1, "a"));
+    assert!(migrations.lock().unwrap().is_empty());
+}
@@ -0,0 +1,208 @@
+#[macro_use]
+extern crate serde_version;
+
+use serde_version::{
+    AggregateVersionMap, ConflictPolicy, RegisterVersionGroupError, VersionGroupResolver,
+    VersionGroupURI, VersionMap, VersionRegistry,
+};
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+struct A;
+struct B;
+
+fn uri(uri: &'static str) -> VersionGroupURI<'static> {
+    VersionGroupURI::try_from(uri).unwrap()
+}
+
+#[test]
+fn register_and_unregister_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+    registry
+        .register(&uri("core:2"), version_map_new! { A => 2, })
+        .unwrap();
+
+    assert_eq!(vec![uri("core:1"), uri("core:2")], registry.uris());
+    assert_eq!(
+        Some(&2),
+        registry
+            .resolver()
+            .resolve(&uri("core:2"))
+            .and_then(|version_map| version_map.get(std::any::type_name::<A>()))
+    );
+
+    assert!(registry.unregister(&uri("core:1")));
+    assert!(!registry.unregister(&uri("core:1")));
+    assert!(!registry.contains(&uri("core:1")));
+    assert!(registry.contains(&uri("core:2")));
+}
+
+#[test]
+fn register_same_version_map_twice_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+}
+
+#[test]
+fn register_conflicting_version_map_fails() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+
+    match registry.register(&uri("core:1"), version_map_new! { A => 2, }) {
+        Err(RegisterVersionGroupError::VersionGroupAlreadyRegistered(_)) => {}
+        _ => panic!("Expected VersionGroupAlreadyRegistered"),
+    }
+}
+
+#[test]
+fn register_type_of_another_api_group_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+    registry
+        .register(&uri("plugin:1"), version_map_new! { A => 1, B => 1, })
+        .unwrap();
+
+    let version_map = ConflictPolicy::AllowEqual
+        .aggregate_version_maps(&[uri("core:1"), uri("plugin:1")], &registry.resolver())
+        .unwrap();
+    assert_eq!(
+        Some(1),
+        VersionMap::get(&version_map, std::any::type_name::<A>())
+    );
+}
+
+#[test]
+fn register_type_of_another_api_group_with_another_version_fails() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+
+    match registry.register(&uri("plugin:1"), version_map_new! { A => 2, B => 1, }) {
+        Err(RegisterVersionGroupError::TypeVersionMismatch(err)) => assert_eq!(
+            format!(
+                "The type {} is defined with the version 2 by version uri plugin:1 and the version 1 by version uri core:1",
+                std::any::type_name::<A>()
+            ),
+            err.to_string()
+        ),
+        _ => panic!("Expected TypeVersionMismatch"),
+    }
+    assert!(!registry.contains(&uri("plugin:1")));
+    // The default registry uses the same policy
+    let registry = VersionRegistry::default();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+    assert!(registry
+        .register(&uri("plugin:1"), version_map_new! { A => 2, })
+        .is_err());
+}
+
+#[test]
+fn register_type_of_another_api_group_with_highest_version_policy_works() {
+    let registry = VersionRegistry::with_conflict_policy(ConflictPolicy::HighestVersion);
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+    registry
+        .register(&uri("plugin:1"), version_map_new! { A => 2, })
+        .unwrap();
+
+    let version_map = ConflictPolicy::HighestVersion
+        .aggregate_version_maps(&[uri("core:1"), uri("plugin:1")], &registry.resolver())
+        .unwrap();
+    assert_eq!(
+        Some(2),
+        VersionMap::get(&version_map, std::any::type_name::<A>())
+    );
+}
+
+#[test]
+fn register_type_of_another_api_group_with_error_policy_fails() {
+    let registry = VersionRegistry::with_conflict_policy(ConflictPolicy::Error);
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+
+    match registry.register(&uri("plugin:1"), version_map_new! { A => 1, B => 1, }) {
+        Err(RegisterVersionGroupError::TypeInMultipleApiGroups(err)) => assert_eq!(
+            format!(
+                "The type {} of version uri plugin:1 is already registered by the api group core",
+                std::any::type_name::<A>()
+            ),
+            err.to_string()
+        ),
+        _ => panic!("Expected TypeInMultipleApiGroups"),
+    }
+    assert!(!registry.contains(&uri("plugin:1")));
+}
+
+#[test]
+fn registry_is_shared_between_threads() {
+    let registry = Arc::new(VersionRegistry::new());
+
+    let threads = (0..4)
+        .map(|index| {
+            let registry = registry.clone();
+            std::thread::spawn(move || {
+                let uri = VersionGroupURI::try_from(format!("core:{}", index)).unwrap();
+                registry
+                    .register(&uri, version_map_new! { A => index, })
+                    .unwrap();
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(4, registry.uris().len());
+}
+
+#[test]
+fn get_outlives_unregister() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+
+    let version_map = registry.get(&uri("core:1")).unwrap();
+    assert!(registry.unregister(&uri("core:1")));
+    assert_eq!(Some(&1), version_map.get(std::any::type_name::<A>()));
+}
+
+#[cfg(feature = "inventory-support")]
+mod static_groups {
+    use super::*;
+
+    register_version_group! {
+        ("static.core", "1") => { B => 3, },
+    }
+
+    #[test]
+    fn register_static_groups_works() {
+        let registry = VersionRegistry::new();
+        registry.register_static_groups().unwrap();
+
+        assert_eq!(
+            Some(&3),
+            registry
+                .resolver()
+                .resolve(&uri("static.core:1"))
+                .and_then(|version_map| version_map.get(std::any::type_name::<B>()))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate serde_version;
+
+use serde_version::{
+    ChainResolver, DefaultVersionMap, DynVersionGroupResolver, VersionGroupResolver,
+    VersionGroupURI,
+};
+use std::convert::TryFrom;
+
+struct A;
+struct B;
+
+fn version_of_a<R: VersionGroupResolver<VM = DefaultVersionMap<'static>>>(
+    resolver: &R,
+    uri: &str,
+) -> Option<usize> {
+    let uri = VersionGroupURI::try_from(uri).unwrap();
+    resolver
+        .resolve(&uri)
+        .and_then(|version_map| version_map.get(std::any::type_name::<A>()).cloned())
+}
+
+#[test]
+fn or_resolver_works() {
+    let core = version_group_resolver_new! {
+        ("core", "1") => { A => 1, },
+    };
+    let plugin = version_group_resolver_new! {
+        ("plugin", "1") => { A => 2, B => 1, },
+        ("core", "1") => { A => 3, },
+    };
+    let resolver = core.or(plugin);
+
+    assert_eq!(Some(1), version_of_a(&resolver, "core:1"));
+    assert_eq!(Some(2), version_of_a(&resolver, "plugin:1"));
+    assert_eq!(None, version_of_a(&resolver, "unknown:1"));
+}
+
+#[test]
+fn chain_resolver_works() {
+    let core = version_group_resolver_new! {
+        ("core", "1") => { A => 1, },
+    };
+    let plugin = version_group_resolver_new! {
+        ("plugin", "1") => { A => 2, B => 1, },
+    };
+    let fixtures = version_group_resolver_new! {
+        ("fixtures", "1") => { A => 3, },
+    };
+
+    let mut resolver: ChainResolver<Box<dyn DynVersionGroupResolver<DefaultVersionMap>>> = vec![
+        Box::new(core) as Box<dyn DynVersionGroupResolver<DefaultVersionMap>>,
+        Box::new(plugin),
+    ]
+    .into();
+    resolver.push(Box::new(fixtures.or(version_group_resolver_new! {
+        ("fallback", "1") => { A => 4, },
+    })));
+
+    assert_eq!(3, resolver.resolvers().len());
+    assert_eq!(Some(1), version_of_a(&resolver, "core:1"));
+    assert_eq!(Some(2), version_of_a(&resolver, "plugin:1"));
+    assert_eq!(Some(3), version_of_a(&resolver, "fixtures:1"));
+    assert_eq!(Some(4), version_of_a(&resolver, "fallback:1"));
+    assert_eq!(None, version_of_a(&resolver, "unknown:1"));
+}
+
+#[test]
+fn map_resolver_works() {
+    let aliases = [(
+        VersionGroupURI::try_from("alias:1").unwrap(),
+        VersionGroupURI::try_from("core:2").unwrap(),
+    )];
+    let resolver = version_group_resolver_new! {
+        ("core", "1") => { A => 1, },
+        ("core", "2") => { A => 2, },
+    }
+    .map_uri(move |uri| {
+        if uri.api_group() == "old_core" {
+            VersionGroupURI::try_from(format!("core:{}", uri.version())).ok()
+        } else {
+            aliases
+                .iter()
+                .find(|(alias, _)| alias == uri)
+                .map(|(_, target)| target.clone())
+        }
+    });
+
+    assert_eq!(Some(1), version_of_a(&resolver, "core:1"));
+    assert_eq!(Some(1), version_of_a(&resolver, "old_core:1"));
+    assert_eq!(Some(2), version_of_a(&resolver, "old_core:2"));
+    assert_eq!(Some(2), version_of_a(&resolver, "alias:1"));
+    assert_eq!(None, version_of_a(&resolver, "old_core:3"));
+}
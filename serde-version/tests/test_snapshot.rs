@@ -0,0 +1,86 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+
+use serde::Deserialize;
+use serde_version::{VersionGroupURI, VersionRegistry, VersionSnapshot, Versioned};
+use std::convert::TryFrom;
+
+#[derive(Deserialize, DeserializeVersioned)]
+#[versions(v(index = 2, self))]
+struct A;
+
+fn uri(uri: &'static str) -> VersionGroupURI<'static> {
+    VersionGroupURI::try_from(uri).unwrap()
+}
+
+#[test]
+fn latest_version_works() {
+    assert_eq!(2, A::LATEST_VERSION);
+}
+
+#[test]
+fn registry_snapshot_works() {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&uri("core:2"), version_map_new! { A => 2, })
+        .unwrap();
+    registry
+        .register(&uri("core:1"), version_map_new! { A => 1, })
+        .unwrap();
+
+    let mut snapshot = registry.snapshot();
+    snapshot.insert_type::<A>();
+
+    assert_eq!(
+        vec![uri("core:1"), uri("core:2")],
+        snapshot
+            .groups
+            .iter()
+            .map(|group| group.uri.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(&2),
+        snapshot.groups[1].types.get(std::any::type_name::<A>())
+    );
+    assert_eq!(Some(&2), snapshot.latest.get(std::any::type_name::<A>()));
+}
+
+#[test]
+fn insert_group_replaces_existing_group() {
+    let mut snapshot = VersionSnapshot::new();
+    snapshot.insert_group(uri("core:1"), &version_map_new! { A => 1, });
+    snapshot.insert_group(uri("core:1"), &version_map_new! { A => 2, });
+
+    assert_eq!(1, snapshot.groups.len());
+    assert_eq!(
+        Some(&2),
+        snapshot.groups[0].types.get(std::any::type_name::<A>())
+    );
+}
+
+#[test]
+fn use_aliases_works() {
+    let mut snapshot = VersionSnapshot::new();
+    snapshot.insert_group(uri("core:1"), &version_map_new! { A => 2, });
+    snapshot.insert_type::<A>();
+    snapshot.use_aliases(&known_types! { A as "core.A", });
+
+    assert_eq!(Some(&2), snapshot.groups[0].types.get("core.A"));
+    assert_eq!(Some(&2), snapshot.latest.get("core.A"));
+    assert_eq!(Some(&vec![2]), snapshot.versions.get("core.A"));
+    assert!(!snapshot.latest.contains_key(std::any::type_name::<A>()));
+}
+
+#[cfg(feature = "inventory-support")]
+#[test]
+fn insert_static_types_works() {
+    let mut snapshot = VersionSnapshot::new();
+    snapshot.insert_static_types();
+
+    assert_eq!(Some(&2), snapshot.latest.get(std::any::type_name::<A>()));
+}
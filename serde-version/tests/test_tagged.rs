@@ -0,0 +1,176 @@
+#![feature(min_specialization)]
+
+#[macro_use]
+extern crate serde_version_derive;
+
+#[macro_use]
+mod common;
+
+use common::A;
+use serde::Deserialize;
+use serde_test::Token;
+use serde_version::DefaultVersionMap;
+use std::fmt::Debug;
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Internal {
+    Newtype(#[serde(deserialize_with = "serde_version::scoped::deserialize")] A),
+    Struct {
+        #[serde(deserialize_with = "serde_version::scoped::deserialize")]
+        a: A,
+    },
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Newtype(#[serde(deserialize_with = "serde_version::scoped::deserialize")] A),
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum Untagged {
+    Newtype(#[serde(deserialize_with = "serde_version::scoped::deserialize")] A),
+}
+
+#[derive(Deserialize)]
+#[serde(rename(deserialize = "E"))]
+enum Ev1 {
+    First(u8),
+}
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(rename(deserialize = "E"))]
+#[versions(v(index = 1, type = "Ev1"), v(index = 2, self))]
+enum E {
+    Second(u8),
+}
+
+impl From<Ev1> for E {
+    fn from(v: Ev1) -> Self {
+        match v {
+            Ev1::First(v) => E::Second(v),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename(deserialize = "Plugin"))]
+enum Pluginv1 {
+    Http { port: u16 },
+}
+
+#[derive(Deserialize, PartialEq, DeserializeVersioned, Debug)]
+#[serde(tag = "type", rename(deserialize = "Plugin"))]
+#[versions(v(index = 1, type = "Pluginv1"), v(index = 2, self))]
+enum Plugin {
+    Http { port: u16 },
+}
+
+impl From<Pluginv1> for Plugin {
+    fn from(v: Pluginv1) -> Self {
+        match v {
+            Pluginv1::Http { port } => Plugin::Http { port },
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct ContainsEnums {
+    e: E,
+    plugin: Plugin,
+}
+
+declare_tests_versions! {
+    scoped test_tagged_version (std::any::type_name::<A>() => 1) {
+        Internal: Internal::Newtype(A { b: 8 }) => &[
+            Token::Map { len: Some(2) },
+                Token::Str("type"),
+                Token::Str("Newtype"),
+                Token::Str("a"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+        Internal: Internal::Struct { a: A { b: 8 } } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("type"),
+                Token::Str("Struct"),
+                Token::Str("a"),
+                Token::Map { len: Some(1) },
+                    Token::Str("a"),
+                    Token::U8(8),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+        Adjacent: Adjacent::Newtype(A { b: 8 }) => &[
+            Token::Map { len: Some(2) },
+                Token::Str("t"),
+                Token::UnitVariant { name: "Adjacent", variant: "Newtype" },
+                Token::Str("c"),
+                Token::Map { len: Some(1) },
+                    Token::Str("a"),
+                    Token::U8(8),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+        Untagged: Untagged::Newtype(A { b: 8 }) => &[
+            Token::Map { len: Some(1) },
+                Token::Str("a"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+    }
+    scoped test_tagged_current_version (std::any::type_name::<A>() => 2) {
+        Internal: Internal::Newtype(A { b: 8 }) => &[
+            Token::Map { len: Some(2) },
+                Token::Str("type"),
+                Token::Str("Newtype"),
+                Token::Str("b"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+        Untagged: Untagged::Newtype(A { b: 8 }) => &[
+            Token::Map { len: Some(1) },
+                Token::Str("b"),
+                Token::U8(8),
+            Token::MapEnd,
+        ],
+    }
+    test_enum_version ("test_tagged::E" => 1, "test_tagged::Plugin" => 1) {
+        E: E::Second(3) => &[
+            Token::NewtypeVariant { name: "E", variant: "First" },
+            Token::U8(3),
+        ],
+        ContainsEnums: ContainsEnums { e: E::Second(3), plugin: Plugin::Http { port: 80 } } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("e"),
+                Token::NewtypeVariant { name: "E", variant: "First" },
+                Token::U8(3),
+                Token::Str("plugin"),
+                Token::Map { len: Some(2) },
+                    Token::Str("kind"),
+                    Token::Str("Http"),
+                    Token::Str("port"),
+                    Token::U16(80),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+    }
+    test_enum_current_version ("test_tagged::E" => 2, "test_tagged::Plugin" => 2) {
+        ContainsEnums: ContainsEnums { e: E::Second(3), plugin: Plugin::Http { port: 80 } } => &[
+            Token::Map { len: Some(2) },
+                Token::Str("e"),
+                Token::NewtypeVariant { name: "E", variant: "Second" },
+                Token::U8(3),
+                Token::Str("plugin"),
+                Token::Map { len: Some(2) },
+                    Token::Str("type"),
+                    Token::Str("Http"),
+                    Token::Str("port"),
+                    Token::U16(80),
+                Token::MapEnd,
+            Token::MapEnd,
+        ],
+    }
+}
@@ -0,0 +1,158 @@
+#![feature(min_specialization)]
+#![cfg(feature = "tokio-support")]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[allow(unused_macros)]
+mod common;
+#[macro_use]
+extern crate lazy_static;
+
+use common::{Av1, A};
+use serde::{Deserialize, Serialize};
+use serde_version::tokio::{
+    deserialize_versioned_async, deserialize_versioned_async_with_limit,
+    deserialize_versioned_blocking, read_frame, write_frame, FrameError,
+};
+use serde_version::{CachedAggregator, VersionGroupURIs};
+use std::convert::TryInto;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+struct Messagev1 {
+    id: u32,
+    a: Av1,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message {
+    id: u32,
+    a: A,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("rpc", "1") => { A => 1, },
+        ("rpc", "2") => { A => 2, },
+    }
+}
+
+fn uris(uri: &'static str) -> VersionGroupURIs<'static> {
+    vec![uri.try_into().unwrap()].into()
+}
+
+async fn frames() -> Vec<u8> {
+    let mut output = Vec::new();
+    write_frame(
+        &mut output,
+        &Messagev1 {
+            id: 1,
+            a: Av1 { a: 5 },
+        },
+        &uris("rpc:1"),
+    )
+    .await
+    .unwrap();
+    write_frame(
+        &mut output,
+        &Message {
+            id: 2,
+            a: A { b: 30 },
+        },
+        &uris("rpc:2"),
+    )
+    .await
+    .unwrap();
+    output
+}
+
+#[tokio::test]
+async fn deserialize_versioned_async_works() {
+    let input = frames().await;
+    let mut reader = input.as_slice();
+
+    let de: Message = deserialize_versioned_async(&mut reader, &*VERSIONS, &())
+        .await
+        .unwrap();
+    assert_eq!(
+        Message {
+            id: 1,
+            a: A { b: 5 }
+        },
+        de
+    );
+    let de: Message = deserialize_versioned_async(&mut reader, &*VERSIONS, &())
+        .await
+        .unwrap();
+    assert_eq!(
+        Message {
+            id: 2,
+            a: A { b: 30 }
+        },
+        de
+    );
+}
+
+#[tokio::test]
+async fn deserialize_versioned_blocking_works() {
+    let input = frames().await;
+    let mut reader = input.as_slice();
+    let aggregator = Arc::new(CachedAggregator::default());
+
+    for (id, b) in [(1, 5), (2, 30)] {
+        let de: Message = deserialize_versioned_blocking(
+            &mut reader,
+            Arc::new(&*VERSIONS),
+            aggregator.clone(),
+            1024,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(Message { id, a: A { b } }, de);
+    }
+}
+
+#[tokio::test]
+async fn read_too_long_frame_fails() {
+    let input = frames().await;
+    match read_frame(&mut input.as_slice(), 4).await {
+        Err(FrameError::FrameTooLong { max_length: 4, .. }) => {}
+        _ => panic!("Expected FrameTooLong"),
+    }
+}
+
+#[tokio::test]
+async fn deserialize_too_long_frame_with_limit_fails() {
+    let input = frames().await;
+    match deserialize_versioned_async_with_limit::<_, Message, _, _>(
+        &mut input.as_slice(),
+        &*VERSIONS,
+        &(),
+        4,
+    )
+    .await
+    {
+        Err(FrameError::FrameTooLong { max_length: 4, .. }) => {}
+        _ => panic!("Expected FrameTooLong"),
+    }
+}
+
+#[tokio::test]
+async fn deserialize_payload_longer_than_frame_fails() {
+    // A frame with an empty vector, whose length prefix is replaced by a huge length
+    let mut payload = serde_version::binary::serialize(&Vec::<u8>::new(), &uris("rpc:2")).unwrap();
+    let length_prefix = payload.len() - 8;
+    payload[length_prefix..].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+    let mut input = (payload.len() as u32).to_be_bytes().to_vec();
+    input.extend(payload);
+
+    match deserialize_versioned_async::<_, Vec<u8>, _, _>(&mut input.as_slice(), &*VERSIONS, &())
+        .await
+    {
+        Err(FrameError::De(_)) => {}
+        _ => panic!("Expected De"),
+    }
+}
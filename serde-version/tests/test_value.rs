@@ -0,0 +1,96 @@
+#![feature(min_specialization)]
+#![cfg(any(
+    feature = "toml-support",
+    feature = "json-support",
+    feature = "ron-support"
+))]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[allow(unused_macros)]
+mod common;
+#[macro_use]
+extern crate lazy_static;
+
+use common::{Container, A};
+use serde_version::value::{deserialize_versioned_from_value, DeserializeValueError};
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("a", "1") => { A => 1, },
+        ("a", "2") => { A => 2, },
+    }
+}
+
+fn expected() -> Container {
+    Container {
+        a: A { b: 5 },
+        others: vec![A { b: 3 }],
+    }
+}
+
+#[cfg(feature = "toml-support")]
+#[test]
+fn deserialize_toml_value_works() {
+    let mut value: toml::Value = toml::from_str(
+        r#"v = ["a:1"]
+others = [{ a = 3 }]
+"#,
+    )
+    .unwrap();
+    // Merge a layer without version header
+    let layer: toml::Value = toml::from_str("a = { a = 5 }").unwrap();
+    if let (toml::Value::Table(table), toml::Value::Table(layer)) = (&mut value, layer) {
+        table.extend(layer);
+    }
+
+    let de: Container = deserialize_versioned_from_value(value, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[cfg(feature = "json-support")]
+#[test]
+fn deserialize_json_value_works() {
+    let value = serde_json::json!({
+        "v": ["a:1"],
+        "a": { "a": 5 },
+        "others": [{ "a": 3 }],
+    });
+
+    let de: Container = deserialize_versioned_from_value(value, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[cfg(feature = "json-support")]
+#[test]
+fn deserialize_value_without_header_fails() {
+    let value = serde_json::json!({ "a": { "b": 5 }, "others": [] });
+
+    match deserialize_versioned_from_value::<Container, _, _, _>(value, &*VERSIONS, &()) {
+        Err(DeserializeValueError::MissingVersionHeader) => {}
+        _ => panic!("Expected MissingVersionHeader"),
+    }
+}
+
+#[cfg(feature = "json-support")]
+#[test]
+fn deserialize_value_with_invalid_header_fails() {
+    let value = serde_json::json!({ "v": ["a"], "a": { "b": 5 }, "others": [] });
+
+    match deserialize_versioned_from_value::<Container, _, _, _>(value, &*VERSIONS, &()) {
+        Err(DeserializeValueError::Header(_)) => {}
+        _ => panic!("Expected Header"),
+    }
+}
+
+#[cfg(feature = "ron-support")]
+#[test]
+fn deserialize_ron_value_works() {
+    let value: ron::Value =
+        ron::de::from_str(r#"{ "v": ["a:2"], "a": { "b": 5 }, "others": [{ "b": 3 }] }"#).unwrap();
+
+    let de: Container = deserialize_versioned_from_value(value, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
@@ -0,0 +1,124 @@
+#![feature(min_specialization)]
+#![cfg(feature = "yaml-support")]
+
+#[macro_use]
+extern crate serde_version;
+#[macro_use]
+extern crate serde_version_derive;
+#[allow(unused_macros)]
+mod common;
+#[macro_use]
+extern crate lazy_static;
+
+use common::A;
+use serde::{Deserialize, Serialize};
+use serde_version::yaml::DeserializeError;
+use serde_version::{VersionGroupURIs, VersionRegistry};
+use std::convert::TryInto;
+
+#[derive(Serialize, Deserialize, VersionedTypes, PartialEq, Debug)]
+struct Container {
+    name: String,
+    a: A,
+    others: Vec<A>,
+}
+
+version_group_resolver_static! {
+    pub VERSIONS = {
+        ("a", "1") => { A => 1, },
+        ("a", "2") => { A => 2, },
+    }
+}
+
+fn registry() -> VersionRegistry {
+    let registry = VersionRegistry::new();
+    registry
+        .register(&"a:1".try_into().unwrap(), version_map_new! { A => 1, })
+        .unwrap();
+    registry
+        .register(&"a:2".try_into().unwrap(), version_map_new! { A => 2, })
+        .unwrap();
+    registry
+}
+
+fn expected() -> Container {
+    Container {
+        name: "config".to_owned(),
+        a: A { b: 5 },
+        others: vec![A { b: 3 }],
+    }
+}
+
+#[test]
+fn deserialize_with_top_level_header_works() {
+    let input = r#"v: ["a:1"]
+name: config
+a:
+  a: 5
+others:
+  - a: 3
+"#;
+    let de: Container = serde_version::yaml::deserialize(input, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[test]
+fn deserialize_with_front_matter_works() {
+    let input = r#"v: ["a:1"]
+---
+name: config
+a:
+  a: 5
+others:
+  - a: 3
+"#;
+    let de: Container = serde_version::yaml::deserialize(input, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[test]
+fn deserialize_without_header_fails() {
+    let input = r#"name: config
+a:
+  b: 5
+others: []
+"#;
+    match serde_version::yaml::deserialize::<Container, _, _>(input, &*VERSIONS, &()) {
+        Err(DeserializeError::MissingVersionHeader) => {}
+        _ => panic!("Expected MissingVersionHeader"),
+    }
+}
+
+#[test]
+fn serialize_works() {
+    let uris: VersionGroupURIs = vec!["a:2".try_into().unwrap()].into();
+
+    let output = serde_version::yaml::serialize(&expected(), &uris).unwrap();
+    assert_eq!(
+        r#"v:
+- a:2
+name: config
+a:
+  b: 5
+others:
+- b: 3
+"#,
+        output
+    );
+    let de: Container = serde_version::yaml::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+
+    let output = serde_version::yaml::serialize_front_matter(&expected(), &uris).unwrap();
+    let de: Container = serde_version::yaml::deserialize(&output, &*VERSIONS, &()).unwrap();
+    assert_eq!(expected(), de);
+}
+
+#[test]
+fn serialize_latest_works() {
+    let output = serde_version::yaml::serialize_latest(&expected(), &registry()).unwrap();
+    let uris: VersionGroupURIs = vec!["a:2".try_into().unwrap()].into();
+    assert_eq!(
+        serde_version::yaml::serialize(&expected(), &uris).unwrap(),
+        output
+    );
+}
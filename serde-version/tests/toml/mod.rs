@@ -1,3 +1,4 @@
+use serde_version::merge::Merge;
 use serde_version::toml::serialize;
 use std::convert::TryInto;
 
@@ -37,7 +38,7 @@ impl From<Bv1> for B {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, VersionedTypes, PartialEq, Debug)]
 struct Container {
     a: A,
     b: B,
@@ -94,6 +95,26 @@ b = 3
     }
 }
 
+#[test]
+fn deserialize_from_reader_works() {
+    let input = r#"v = ["a:1", "b:2"]
+[a]
+a = 5
+
+[b]
+b = 3
+"#;
+    let de: Container =
+        serde_version::toml::deserialize_from_reader(input.as_bytes(), &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        Container {
+            a: A { b: 5 },
+            b: B { b: 3 }
+        },
+        de
+    );
+}
+
 #[test]
 fn serialize_works() {
     let v = serialize(
@@ -115,3 +136,299 @@ b = 3
         v
     );
 }
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct FlattenContainer {
+    name: String,
+    #[serde(flatten, with = "serde_version::scoped")]
+    container: Container,
+}
+
+#[test]
+fn deserialize_flatten_works() {
+    let input = r#"v = ["a:1", "b:1"]
+name = "config"
+
+[a]
+a = 5
+
+[b]
+a = 3
+"#;
+    let de: FlattenContainer = serde_version::toml::deserialize(input, &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        FlattenContainer {
+            name: "config".to_owned(),
+            container: Container {
+                a: A { b: 5 },
+                b: B { b: 3 }
+            },
+        },
+        de
+    );
+}
+
+fn manifest() -> String {
+    format!(
+        r#"[[groups]]
+uri = "a:1"
+types = {{ "{a}" = 1 }}
+
+[[groups]]
+uri = "b:2"
+types = {{ "{b}" = 2 }}
+"#,
+        a = std::any::type_name::<A>(),
+        b = std::any::type_name::<B>(),
+    )
+}
+
+#[test]
+fn load_manifest_works() {
+    let registry =
+        serde_version::toml::load_manifest(&manifest(), &known_types! { A, B, }).unwrap();
+
+    let input = r#"v = ["a:1", "b:2"]
+[a]
+a = 5
+
+[b]
+b = 3
+"#;
+    let de: Container = serde_version::toml::deserialize(input, &registry.resolver(), &()).unwrap();
+    assert_eq!(
+        Container {
+            a: A { b: 5 },
+            b: B { b: 3 }
+        },
+        de
+    );
+}
+
+#[test]
+fn load_manifest_with_unknown_type_fails() {
+    match serde_version::toml::load_manifest(&manifest(), &known_types! { A, }) {
+        Err(serde_version::toml::LoadManifestError::Manifest(
+            serde_version::ManifestError::UnknownTypeId(err),
+        )) => assert_eq!(
+            format!(
+                "The type {} of version uri b:2 is unknown to this binary",
+                std::any::type_name::<B>()
+            ),
+            err.to_string()
+        ),
+        _ => panic!("Expected UnknownTypeId"),
+    }
+}
+
+#[test]
+fn snapshot_can_be_loaded_as_manifest() {
+    let mut snapshot = serde_version::toml::load_manifest(&manifest(), &known_types! { A, B, })
+        .unwrap()
+        .snapshot();
+    snapshot.insert_type::<A>();
+
+    let output = ::toml::to_string(&snapshot).unwrap();
+    let manifest: serde_version::VersionManifest = ::toml::from_str(&output).unwrap();
+    assert_eq!(snapshot.groups, manifest.groups);
+}
+
+#[test]
+fn serialize_latest_works() {
+    let registry = serde_version::VersionRegistry::new();
+    for (uri, version_map) in [
+        ("a:1", version_map_new! { A => 1, }),
+        ("a:2", version_map_new! { A => 2, }),
+        ("b:2", version_map_new! { B => 2, }),
+    ] {
+        registry
+            .register(&uri.try_into().unwrap(), version_map)
+            .unwrap();
+    }
+
+    let value = Container {
+        a: A { b: 5 },
+        b: B { b: 3 },
+    };
+    let output = serde_version::toml::serialize_latest(&value, &registry).unwrap();
+    assert_eq!(
+        r#"v = ["a:2", "b:2"]
+[a]
+b = 5
+
+[b]
+b = 3
+"#,
+        output
+    );
+}
+
+#[test]
+fn serialize_latest_without_latest_group_fails() {
+    let registry = serde_version::VersionRegistry::new();
+    registry
+        .register(&"a:2".try_into().unwrap(), version_map_new! { A => 2, })
+        .unwrap();
+
+    let value = Container {
+        a: A { b: 5 },
+        b: B { b: 3 },
+    };
+    match serde_version::toml::serialize_latest(&value, &registry) {
+        Err(serde_version::toml::SerializeError::UncoveredType(err)) => assert_eq!(
+            format!(
+                "No version group defines the version 2 of the type {}",
+                std::any::type_name::<B>()
+            ),
+            err.to_string()
+        ),
+        _ => panic!("Expected UncoveredType"),
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug, Default)]
+struct PartialContainer {
+    #[serde(default, with = "serde_version::option")]
+    a: Option<A>,
+    #[serde(default, with = "serde_version::option")]
+    b: Option<B>,
+}
+
+impl Merge for PartialContainer {
+    fn merge(&mut self, layer: Self) {
+        self.a.merge(layer.a);
+        self.b.merge(layer.b);
+    }
+}
+
+#[test]
+fn merge_layers_works() {
+    let default = r#"v = ["a:1", "b:1"]
+[a]
+a = 1
+
+[b]
+a = 2
+"#;
+    let user = r#"v = ["a:2"]
+[a]
+b = 5
+"#;
+    let project = r#"v = ["b:2"]
+"#;
+
+    let merged: PartialContainer =
+        serde_version::toml::merge_layers(vec![default, user, project], &*VERSIONS, &()).unwrap();
+    assert_eq!(
+        PartialContainer {
+            a: Some(A { b: 5 }),
+            b: Some(B { b: 2 }),
+        },
+        merged
+    );
+}
+
+#[test]
+fn merge_layers_reports_the_failed_layer() {
+    let default = r#"v = ["a:1"]
+"#;
+    let user = r#"v = ["a:3"]
+"#;
+
+    match serde_version::toml::merge_layers::<PartialContainer, _, _, _>(
+        vec![default, user],
+        &*VERSIONS,
+        &(),
+    ) {
+        Err(err) => assert_eq!(1, err.index),
+        _ => panic!("Expected LayerError"),
+    }
+}
+
+#[test]
+fn merge_toml_values_works() {
+    let mut value: ::toml::Value = ::toml::from_str("[a]\nb = 1\nc = 2\n").unwrap();
+    value.merge(::toml::from_str("[a]\nc = 3\n[b]\nb = 4\n").unwrap());
+
+    let expected: ::toml::Value = ::toml::from_str("[a]\nb = 1\nc = 3\n[b]\nb = 4\n").unwrap();
+    assert_eq!(expected, value);
+}
+
+#[test]
+fn migrate_file_works() {
+    let path =
+        std::env::temp_dir().join(format!("serde_version_migrate_{}.toml", std::process::id()));
+    let original = r#"v = ["a:1", "b:2"]
+[a]
+a = 5
+
+[b]
+b = 3
+"#;
+    let migrated = r#"v = ["a:2", "b:2"]
+[a]
+b = 5
+
+[b]
+b = 3
+"#;
+    let latest = vec!["a:2".try_into().unwrap(), "b:2".try_into().unwrap()].into();
+    std::fs::write(&path, original).unwrap();
+
+    let migration =
+        serde_version::toml::migrate_file::<Container, _, _>(&path, &*VERSIONS, &(), &latest, true)
+            .unwrap();
+    assert!(migration.is_changed());
+    assert_eq!(migrated, migration.migrated);
+    assert_eq!(original, std::fs::read_to_string(&path).unwrap());
+
+    serde_version::toml::migrate_file::<Container, _, _>(&path, &*VERSIONS, &(), &latest, false)
+        .unwrap();
+    assert_eq!(migrated, std::fs::read_to_string(&path).unwrap());
+
+    let migration = serde_version::toml::migrate_file::<Container, _, _>(
+        &path,
+        &*VERSIONS,
+        &(),
+        &latest,
+        false,
+    )
+    .unwrap();
+    assert!(!migration.is_changed());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn migrate_str_latest_works() {
+    let registry = serde_version::VersionRegistry::new();
+    for (uri, version_map) in [
+        ("a:1", version_map_new! { A => 1, }),
+        ("a:2", version_map_new! { A => 2, }),
+        ("b:2", version_map_new! { B => 2, }),
+    ] {
+        registry
+            .register(&uri.try_into().unwrap(), version_map)
+            .unwrap();
+    }
+
+    let input = r#"v = ["a:1", "b:2"]
+[a]
+a = 5
+
+[b]
+b = 3
+"#;
+    let migrated =
+        serde_version::toml::migrate_str_latest::<Container, _>(input, &registry, &()).unwrap();
+    assert_eq!(
+        r#"v = ["a:2", "b:2"]
+[a]
+b = 5
+
+[b]
+b = 3
+"#,
+        migrated
+    );
+}
@@ -4,6 +4,23 @@ pub struct Container<'a> {
     pub ident: syn::Ident,
     pub attrs: attr::Container,
     pub generics: &'a syn::Generics,
+    /// The types of the fields, of all variants for enums, without duplicates
+    pub field_types: Vec<&'a syn::Type>,
+    pub wrapper: Wrapper<'a>,
+}
+
+/// A struct wrapping a single field
+pub enum Wrapper<'a> {
+    /// Not a newtype struct nor a transparent struct
+    None,
+    /// A newtype struct: `struct A(B);`
+    Newtype,
+    /// A struct with a single field and `#[serde(transparent)]`,
+    /// the field has no serde attributes
+    Transparent {
+        member: syn::Member,
+        ty: &'a syn::Type,
+    },
 }
 
 pub mod symbols {
@@ -12,6 +29,8 @@ pub mod symbols {
     pub const DEFAULT: Symbol = Symbol("default");
     pub const INDEX: Symbol = Symbol("index");
     pub const SELF: Symbol = Symbol("self");
+    pub const SERDE: Symbol = Symbol("serde");
+    pub const TRANSPARENT: Symbol = Symbol("transparent");
     pub const TYPE: Symbol = Symbol("type");
     pub const VERSIONS: Symbol = Symbol("versions");
     pub const VERSION: Symbol = Symbol("version");
@@ -19,12 +38,12 @@ pub mod symbols {
 }
 
 pub mod attr {
-    use super::symbols::{DEFAULT, INDEX, SELF, TYPE, VERSION, VERSION_SHORTHAND, VERSIONS};
+    use super::super::util::get_serde_version_meta_items;
+    use super::symbols::{DEFAULT, INDEX, SELF, TYPE, VERSION, VERSIONS, VERSION_SHORTHAND};
     use proc_macro_util::prelude::{Attr, Ctxt};
     use quote::ToTokens;
     use std::collections::HashMap;
     use syn::{Meta, NestedMeta};
-    use super::super::util::get_serde_version_meta_items;
 
     pub struct Container {
         versions: Option<Versions>,
@@ -37,9 +56,7 @@ pub mod attr {
             let mut self_version_defined = false;
 
             match item.data {
-                syn::Data::Struct(syn::DataStruct {
-                    ref struct_token, ..
-                }) => {
+                syn::Data::Struct(_) | syn::Data::Enum(_) => {
                     let mut error_message = None;
                     let mut parsed_versions = HashMap::new();
 
@@ -174,21 +191,23 @@ pub mod attr {
                     });
 
                     if let Some(error_message) = error_message {
+                        let token = match item.data {
+                            syn::Data::Struct(ref data) => data.struct_token.into_token_stream(),
+                            syn::Data::Enum(ref data) => data.enum_token.into_token_stream(),
+                            syn::Data::Union(_) => unreachable!("Handled in the next arm"),
+                        };
                         cx.error_spanned_by(
-                            struct_token,
+                            token,
                             format!("Error while parsing the attribute: {}.", error_message),
                         );
                     }
                 }
-                syn::Data::Enum(syn::DataEnum { ref enum_token, .. }) => {
-                    cx.error_spanned_by(enum_token, "#[versions(...)] can only be used on structs");
-                }
                 syn::Data::Union(syn::DataUnion {
                     ref union_token, ..
                 }) => {
                     cx.error_spanned_by(
                         union_token,
-                        "#[versions(...)] can only be used on structs",
+                        "#[versions(...)] can only be used on structs and enums",
                     );
                 }
             }
@@ -236,17 +255,80 @@ impl<'a> Container<'a> {
             ident: item.ident.clone(),
             attrs,
             generics: &item.generics,
+            field_types: field_types(item),
+            wrapper: wrapper(item),
+        }
+    }
+}
+
+/// Whether the item is a newtype struct or a transparent struct with a single field
+pub fn wrapper(item: &syn::DeriveInput) -> Wrapper<'_> {
+    let fields = match item.data {
+        syn::Data::Struct(ref data) => &data.fields,
+        _ => return Wrapper::None,
+    };
+    let is_transparent = item.attrs.iter().any(|attr| {
+        attr.path == symbols::SERDE
+            && match attr.parse_meta() {
+                Ok(syn::Meta::List(ref meta)) => meta.nested.iter().any(|nested| match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(ref path)) => {
+                        path == symbols::TRANSPARENT
+                    }
+                    _ => false,
+                }),
+                _ => false,
+            }
+    });
+
+    let mut iter = fields.iter();
+    match (iter.next(), iter.next()) {
+        // The serde attributes of the field (`with`, `deserialize_with`, `default`, ...) are only
+        // honored by the `Deserialize` implementation of the struct
+        (Some(field), None)
+            if is_transparent && field.attrs.iter().any(|attr| attr.path == symbols::SERDE) =>
+        {
+            Wrapper::None
         }
+        (Some(field), None) if is_transparent => Wrapper::Transparent {
+            member: match field.ident {
+                Some(ref ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(0.into()),
+            },
+            ty: &field.ty,
+        },
+        (Some(field), None) if field.ident.is_none() => Wrapper::Newtype,
+        _ => Wrapper::None,
     }
 }
 
+/// The types of the fields, of all variants for enums, without duplicates
+pub fn field_types(item: &syn::DeriveInput) -> Vec<&syn::Type> {
+    let fields: Box<dyn Iterator<Item = &syn::Field>> = match item.data {
+        syn::Data::Struct(ref data) => Box::new(data.fields.iter()),
+        syn::Data::Enum(ref data) => Box::new(
+            data.variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter()),
+        ),
+        syn::Data::Union(ref data) => Box::new(data.fields.named.iter()),
+    };
+    let mut field_types = Vec::new();
+    let mut field_type_names = std::collections::HashSet::new();
+    for field in fields {
+        if field_type_names.insert(quote::ToTokens::to_token_stream(&field.ty).to_string()) {
+            field_types.push(&field.ty);
+        }
+    }
+    field_types
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ast::attr::PathOrSelf;
+    use crate::ast::Container;
     use proc_macro_util::prelude::Ctxt;
     use quote::ToTokens;
     use std::collections::HashMap;
-    use crate::ast::attr::PathOrSelf;
-    use crate::ast::Container;
 
     #[test]
     fn parse_container() {
@@ -296,4 +378,45 @@ mod tests {
                 .collect::<HashMap<_, _>>()
         );
     }
+
+    #[test]
+    fn parse_enum_container() {
+        let item: proc_macro2::TokenStream = quote! {
+            #[versions(v(index = 1, type = "Ev1"), v(index = 2, self))]
+            enum E { A(u8), B { b: u8 }, }
+        };
+
+        let item = syn::parse2::<syn::DeriveInput>(item).unwrap();
+
+        let cx = Ctxt::new();
+        let cont = Container::from_ast(&cx, &item);
+        cx.check().unwrap();
+
+        assert_eq!("E".to_owned(), cont.ident.to_string());
+        assert_eq!(
+            std::ops::Deref::deref(cont.attrs.versions().unwrap()).len(),
+            2
+        );
+        assert_eq!(
+            vec!["u8".to_owned()],
+            cont.field_types
+                .iter()
+                .map(|ty| ty.to_token_stream().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_union_container_fails() {
+        let item: proc_macro2::TokenStream = quote! {
+            #[versions(v(index = 1, type = "Uv1"), v(index = 2, self))]
+            union U { a: u8, b: u16, }
+        };
+
+        let item = syn::parse2::<syn::DeriveInput>(item).unwrap();
+
+        let cx = Ctxt::new();
+        Container::from_ast(&cx, &item);
+        assert!(cx.check().is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use proc_macro_util::prelude::*;
 use crate::ast::attr::PathOrSelf;
-use crate::ast::Container;
+use crate::ast::{Container, Wrapper};
 
 pub fn expand_derive_deserialize_versioned(
     input: &syn::DeriveInput,
@@ -63,7 +63,10 @@ pub fn expand_derive_deserialize_versioned(
                         Some(quote! {
                             Some(#version_number) => std::result::Result::map(
                                 <#path as _serde_version::DeserializeVersioned<'_, __VM>>::deserialize_versioned(__deserializer, __version_map),
-                                std::convert::Into::into
+                                |v| {
+                                    _serde_version::observer::notify(#deser_name, #version_number, #last_version);
+                                    std::convert::Into::into(v)
+                                }
                             ),
                         })
                     }
@@ -82,7 +85,10 @@ pub fn expand_derive_deserialize_versioned(
                         Some(quote! {
                             Some(#version_number) => std::result::Result::map(
                                 <#path as _serde_version::DeserializeVersioned<'_, __VM>>::next_element(__seq_access, __version_map),
-                                |v| std::option::Option::map(v, std::convert::Into::into)
+                                |v| std::option::Option::map(v, |v| {
+                                    _serde_version::observer::notify(#deser_name, #version_number, #last_version);
+                                    std::convert::Into::into(v)
+                                })
                             ),
                         })
                     }
@@ -101,7 +107,10 @@ pub fn expand_derive_deserialize_versioned(
                         Some(quote! {
                             Some(#version_number) => std::result::Result::map(
                                 <#path as _serde_version::DeserializeVersioned<'_, __VM>>::next_value(__map_access, __version_map),
-                                std::convert::Into::into
+                                |v| {
+                                    _serde_version::observer::notify(#deser_name, #version_number, #last_version);
+                                    std::convert::Into::into(v)
+                                }
                             ),
                         })
                     }
@@ -124,7 +133,10 @@ pub fn expand_derive_deserialize_versioned(
                                     __map_access,
                                     __version_map
                                 ),
-                                |v| std::option::Option::map(v, std::convert::Into::into)
+                                |v| std::option::Option::map(v, |v| {
+                                    _serde_version::observer::notify(#deser_name, #version_number, #last_version);
+                                    std::convert::Into::into(v)
+                                })
                             ),
                         })
                     } else {
@@ -146,7 +158,10 @@ pub fn expand_derive_deserialize_versioned(
                                     __enum_access,
                                     __version_map
                                 ),
-                                |(v, variant)| (std::convert::Into::into(v), variant)
+                                |(v, variant)| {
+                                    _serde_version::observer::notify(#deser_name, #version_number, #last_version);
+                                    (std::convert::Into::into(v), variant)
+                                }
                             ),
                         })
                     } else {
@@ -155,7 +170,135 @@ pub fn expand_derive_deserialize_versioned(
                 })
                 .collect::<Vec<_>>();
 
+            // The latest version of a transparent struct is its field,
+            // it is deserialized with versioning support.
+            let (
+                latest_deserialize,
+                latest_next_element,
+                latest_next_value,
+                latest_next_key,
+                latest_variant,
+            ) = match cont.wrapper {
+                Wrapper::Transparent { ref member, ty } => (
+                    quote! {
+                        std::result::Result::map(
+                            <#ty as _serde_version::DeserializeVersioned<'de, __VM>>::deserialize_versioned(__deserializer, __version_map),
+                            |__field| Self { #member: __field }
+                        )
+                    },
+                    quote! {
+                        std::result::Result::map(
+                            <#ty as _serde_version::DeserializeVersioned<'de, __VM>>::next_element(__seq_access, __version_map),
+                            |v| std::option::Option::map(v, |__field| Self { #member: __field })
+                        )
+                    },
+                    quote! {
+                        std::result::Result::map(
+                            <#ty as _serde_version::DeserializeVersioned<'de, __VM>>::next_value(__map_access, __version_map),
+                            |__field| Self { #member: __field }
+                        )
+                    },
+                    quote! {
+                        std::result::Result::map(
+                            <#ty as _serde_version::DeserializeVersioned<'de, __VM>>::next_key(__map_access, __version_map),
+                            |v| std::option::Option::map(v, |__field| Self { #member: __field })
+                        )
+                    },
+                    quote! {
+                        std::result::Result::map(
+                            <#ty as _serde_version::DeserializeVersioned<'de, __VM>>::variant(__enum_access, __version_map),
+                            |(__field, variant)| (Self { #member: __field }, variant)
+                        )
+                    },
+                ),
+                _ => (
+                    quote! {
+                        <Self as _serde::Deserialize<'de>>::deserialize(__deserializer)
+                            .map_err(_serde_version::Error::DeserializeError)
+                    },
+                    quote! {
+                        <__S as _serde::de::SeqAccess<'de>>::next_element_seed(
+                            __seq_access,
+                            std::marker::PhantomData
+                        ).map_err(_serde_version::Error::DeserializeError)
+                    },
+                    quote! {
+                        <__M as _serde::de::MapAccess<'de>>::next_value_seed(
+                            __map_access,
+                            std::marker::PhantomData
+                        ).map_err(_serde_version::Error::DeserializeError)
+                    },
+                    quote! {
+                        <__M as _serde::de::MapAccess<'de>>::next_key_seed(
+                            __map_access,
+                            std::marker::PhantomData
+                        ).map_err(_serde_version::Error::DeserializeError)
+                    },
+                    quote! {
+                        <__E as _serde::de::EnumAccess<'de>>::variant_seed(
+                            __enum_access,
+                            std::marker::PhantomData
+                        ).map_err(_serde_version::Error::DeserializeError)
+                    },
+                ),
+            };
+            // The visitor generated by `#[derive(Deserialize)]` for a newtype struct accepts
+            // its content as a sequence, which is deserialized with versioning support.
+            let visit_newtype_as_seq_impl = match cont.wrapper {
+                Wrapper::Newtype => {
+                    let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+                    quote! {
+                        impl #impl_generics _serde_version::VisitNewtypeAsSeq for #ident #ty_generics #where_clause {
+                            fn visit_newtype_as_seq() -> bool {
+                                true
+                            }
+                        }
+                    }
+                }
+                _ => TokenStream::new(),
+            };
+
+            let versioned_impl = {
+                let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+                let mut version_numbers = versions.keys().collect::<Vec<_>>();
+                version_numbers.sort();
+                quote! {
+                    impl #impl_generics _serde_version::Versioned for #ident #ty_generics #where_clause {
+                        const LATEST_VERSION: usize = #last_version;
+                        const VERSIONS: &'static [usize] = &[#(#version_numbers),*];
+                    }
+                }
+            };
+            let versioned_types_impl = {
+                let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+                let field_types = &cont.field_types;
+                quote! {
+                    impl #impl_generics _serde_version::VersionedTypes for #ident #ty_generics #where_clause {
+                        fn versioned_types(__types: &mut _serde_version::VersionedTypeSet) {
+                            if __types.visit(#deser_name) {
+                                __types.insert(#deser_name, #last_version);
+                                #(<#field_types as _serde_version::VersionedTypes>::versioned_types(__types);)*
+                            }
+                        }
+                    }
+                }
+            };
+            // Generic types can't be collected statically
+            let submit_versioned_type = if cont.generics.params.is_empty() {
+                quote! { _serde_version::__submit_versioned_type!(#ident); }
+            } else {
+                TokenStream::new()
+            };
+
             let code = quote! {
+                #versioned_impl
+
+                #versioned_types_impl
+
+                #visit_newtype_as_seq_impl
+
+                #submit_versioned_type
+
                 impl #de_impl_generics _serde_version::DeserializeVersioned<'de, __VM> for #ident #ty_generics #where_clause {
                     fn deserialize_versioned<__D>(
                         __deserializer: __D,
@@ -165,8 +308,7 @@ pub fn expand_derive_deserialize_versioned(
                         __D: _serde::Deserializer<'de>, {
                         match __version_map.get(#deser_name) {
                             #(#deserialize_arms)*
-                            None | Some(#last_version) => <Self as _serde::Deserialize<'de>>::deserialize(__deserializer)
-                                .map_err(_serde_version::Error::DeserializeError),
+                            None | Some(#last_version) => #latest_deserialize,
                             Some(v) => Err(_serde_version::Error::InvalidVersionError(
                                 _serde_version::InvalidVersionError {
                                     version: v,
@@ -186,10 +328,7 @@ pub fn expand_derive_deserialize_versioned(
                     {
                         match __version_map.get(#deser_name) {
                             #(#next_element_arms)*
-                            None | Some(#last_version) => <__S as _serde::de::SeqAccess<'de>>::next_element_seed(
-                                __seq_access,
-                                std::marker::PhantomData
-                            ).map_err(_serde_version::Error::DeserializeError),
+                            None | Some(#last_version) => #latest_next_element,
                             Some(v) => Err(_serde_version::Error::InvalidVersionError(
                                 _serde_version::InvalidVersionError {
                                     version: v,
@@ -209,10 +348,7 @@ pub fn expand_derive_deserialize_versioned(
                     {
                         match __version_map.get(#deser_name) {
                             #(#next_value_arms)*
-                            None | Some(#last_version) => <__M as _serde::de::MapAccess<'de>>::next_value_seed(
-                                __map_access,
-                                std::marker::PhantomData
-                            ).map_err(_serde_version::Error::DeserializeError),
+                            None | Some(#last_version) => #latest_next_value,
                             Some(v) => Err(_serde_version::Error::InvalidVersionError(
                                 _serde_version::InvalidVersionError {
                                     version: v,
@@ -232,10 +368,7 @@ pub fn expand_derive_deserialize_versioned(
                     {
                         match __version_map.get(#deser_name) {
                             #(#next_key_arms)*
-                            None | Some(#last_version) => <__M as _serde::de::MapAccess<'de>>::next_key_seed(
-                                __map_access,
-                                std::marker::PhantomData
-                            ).map_err(_serde_version::Error::DeserializeError),
+                            None | Some(#last_version) => #latest_next_key,
                             Some(v) => Err(_serde_version::Error::InvalidVersionError(
                                 _serde_version::InvalidVersionError {
                                     version: v,
@@ -255,10 +388,7 @@ pub fn expand_derive_deserialize_versioned(
                     {
                         match __version_map.get(#deser_name) {
                             #(#variant_arms)*
-                            None | Some(#last_version) => <__E as _serde::de::EnumAccess<'de>>::variant_seed(
-                                __enum_access,
-                                std::marker::PhantomData
-                            ).map_err(_serde_version::Error::DeserializeError),
+                            None | Some(#last_version) => #latest_variant,
                             Some(v) => Err(_serde_version::Error::InvalidVersionError(
                                 _serde_version::InvalidVersionError {
                                     version: v,
@@ -280,3 +410,20 @@ pub fn expand_derive_deserialize_versioned(
         None => Ok(TokenStream::new()),
     }
 }
+
+pub fn expand_derive_versioned_types(input: &syn::DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let field_types = crate::ast::field_types(input);
+
+    let code = quote! {
+        impl #impl_generics _serde_version::VersionedTypes for #ident #ty_generics #where_clause {
+            fn versioned_types(__types: &mut _serde_version::VersionedTypeSet) {
+                if __types.visit(std::any::type_name::<Self>()) {
+                    #(<#field_types as _serde_version::VersionedTypes>::versioned_types(__types);)*
+                }
+            }
+        }
+    };
+    crate::util::wrap_in_const(None, None, "VERSIONED_TYPES", ident, code)
+}
@@ -17,6 +17,12 @@ pub fn derive_deserialize_versioned(input: proc_macro::TokenStream) -> proc_macr
         .into()
 }
 
+#[proc_macro_derive(VersionedTypes)]
+pub fn derive_versioned_types(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse::<syn::DeriveInput>(input).unwrap();
+    de::expand_derive_versioned_types(&input).into()
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)